@@ -94,7 +94,7 @@ pub fn show_move_to() -> Result<()> {
 
     let txt = "centered";
     let size = term_size()?;
-    let x = (size.char_width - txt.len() + 1) / 2;
+    let x = (size.char_width - txt.len()).div_ceil(2);
     let y = size.char_height / 2;
     // If one of arguments is not literal, produces string.
     let center: String = codes::move_to!(x, y);
@@ -1,13 +1,8 @@
-use std::io::{Write, stdout};
-
 use termal::{
-    codes,
     error::Result,
-    formatc,
     raw::{
         StdioProvider, Terminal, disable_raw_mode, enable_raw_mode,
-        events::{Event, Key, KeyCode},
-        readers::TermRead,
+        readers::{TermRead, WordCompleter},
     },
 };
 
@@ -16,46 +11,20 @@ fn main() -> Result<()> {
 
     let mut terminal = Terminal::<StdioProvider>::default();
     let mut reader = TermRead::lines(&mut terminal);
-    let mut buf = String::new();
-
-    while unsafe { !reader.read_one()? } {
-        // What has been read.
-        let read: &[char] = reader.get_input();
-        // Position in what has been read.
-        let pos: usize = reader.get_pos();
-
-        let word = "autocomplete";
-        let to_complete = &word[read.len().min(word.len())..];
-
-        if to_complete.is_empty() {
-            continue;
-        }
 
-        if matches!(
-            reader.last_event(),
-            Some(Event::KeyPress(Key {
-                code: KeyCode::Tab,
-                ..
-            }))
-        ) {
-            let rl = read.len();
-            reader.splice(rl..rl, to_complete.chars());
-            reader.reshow()?;
-            reader.queue([Event::KeyPress(Key::code(KeyCode::End))]);
-        } else {
-            buf.clear();
-            buf += codes::CUR_SAVE;
-            buf.extend(&read[pos..]);
-            buf +=
-                &formatc!("{'gr}{}{'_}", &word[read.len().min(word.len())..]);
-            buf += codes::CUR_LOAD;
-            print!("{buf}");
-            _ = stdout().flush();
-        }
-    }
+    let word = "autocomplete";
+    // Complete with tab.
+    reader.set_completer(WordCompleter::new([word]));
+    // Show the rest of the word after cursor. Accept with right arrow.
+    reader.set_hinter(|read: &[char], _| {
+        let read: String = read.iter().collect();
+        word.strip_prefix(&read)
+            .filter(|h| !h.is_empty())
+            .map(|h| h.to_string())
+    });
 
     // get the entered string
-    let s = reader.finish()?;
+    let s = reader.read_str()?;
 
     disable_raw_mode()?;
 
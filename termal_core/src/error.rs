@@ -16,6 +16,14 @@ pub enum Error {
     WaitAbandoned,
     #[error("Failed to parse rgb.")]
     InvalidRgbFormat,
+    /// The data is too large to be written to clipboard.
+    #[error(
+        "Clipboard data is too large ({len} bytes encoded, maximum is {max})."
+    )]
+    ClipboardTooLarge { len: usize, max: usize },
+    /// The terminal doesn't allow reading the clipboard.
+    #[error("The terminal refused to read the clipboard.")]
+    ClipboardRefused,
     /// Any IO error.
     #[error(transparent)]
     Io(#[from] std::io::Error),
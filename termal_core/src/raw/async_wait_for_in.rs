@@ -0,0 +1,167 @@
+use std::{
+    io::{Stdin, StdinLock},
+    mem,
+    sync::{Arc, LazyLock, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+    thread,
+    time::Duration,
+};
+
+use crate::error::Result;
+
+use super::{
+    StdioProvider, WaitForIn, WakeSignal, wait_for_stdin, wait_for_stdin_or,
+};
+
+/// The type can asynchronously wait for input.
+pub trait AsyncWaitForIn: WaitForIn {
+    /// Checks whether there is input available. If there is no input
+    /// available, returns [`Poll::Pending`] and the waker from `cx` will be
+    /// woken once there is input.
+    fn poll_for_in(&self, cx: &mut Context<'_>) -> Poll<Result<()>>;
+
+    /// Stop waiting for input started by [`AsyncWaitForIn::poll_for_in`].
+    /// Tasks that still wait are woken so that they may poll again. By
+    /// default this does nothing.
+    fn cancel_poll(&self) {}
+}
+
+impl AsyncWaitForIn for StdioProvider {
+    fn poll_for_in(&self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        poll_for_stdin(cx)
+    }
+
+    fn cancel_poll(&self) {
+        STDIN_WATCH.cancel();
+    }
+}
+
+impl AsyncWaitForIn for Stdin {
+    fn poll_for_in(&self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        poll_for_stdin(cx)
+    }
+
+    fn cancel_poll(&self) {
+        STDIN_WATCH.cancel();
+    }
+}
+
+impl AsyncWaitForIn for StdinLock<'static> {
+    fn poll_for_in(&self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        poll_for_stdin(cx)
+    }
+
+    fn cancel_poll(&self) {
+        STDIN_WATCH.cancel();
+    }
+}
+
+static STDIN_WATCH: LazyLock<InputWatch> = LazyLock::new(InputWatch::new);
+
+/// Checks whether there is input on stdin. If there is no input, the waker
+/// from `cx` will be woken once there is.
+///
+/// The waker is registered with thread that waits for stdin to become
+/// readable. The thread exits once it wakes the waiting tasks or when the
+/// waiting is cancelled with [`AsyncWaitForIn::cancel_poll`], so it runs only
+/// while some task waits for input.
+pub fn poll_for_stdin(cx: &mut Context<'_>) -> Poll<Result<()>> {
+    if wait_for_stdin(Duration::ZERO)? {
+        return Poll::Ready(Ok(()));
+    }
+
+    STDIN_WATCH.poll(cx, || {
+        Ok(|s: &WakeSignal| wait_for_stdin_or(s, Duration::MAX))
+    })
+}
+
+/// Wakes tasks waiting for input. The input is waited for on separate thread
+/// that may be interrupted with [`WakeSignal`].
+#[derive(Debug)]
+pub(crate) struct InputWatch(Arc<Mutex<WatchState>>);
+
+#[derive(Debug, Default)]
+struct WatchState {
+    wakers: Vec<Waker>,
+    /// Signal that interrupts the running thread. `None` if there is no
+    /// thread waiting for input.
+    signal: Option<Arc<WakeSignal>>,
+}
+
+impl InputWatch {
+    pub(crate) fn new() -> Self {
+        Self(Arc::default())
+    }
+
+    /// Register the waker from `cx` and start thread that waits for input if
+    /// it is not running. The thread waits with the function created by
+    /// `wait`. The function waits for input or until the signal is set and
+    /// returns `true` if there is input.
+    pub(crate) fn poll<W>(
+        &self,
+        cx: &mut Context<'_>,
+        wait: impl FnOnce() -> Result<W>,
+    ) -> Poll<Result<()>>
+    where
+        W: Fn(&WakeSignal) -> Result<bool> + Send + 'static,
+    {
+        let mut state = lock(&self.0);
+        if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            state.wakers.push(cx.waker().clone());
+        }
+        if state.signal.is_none() {
+            let signal = Arc::new(WakeSignal::new()?);
+            let wait = wait()?;
+            let watch = self.0.clone();
+            let sig = signal.clone();
+            thread::Builder::new()
+                .name("termal-input-watch".into())
+                .spawn(move || watch_input(&watch, &sig, wait))?;
+            state.signal = Some(signal);
+        }
+
+        Poll::Pending
+    }
+
+    /// Interrupt the thread that waits for input. The waiting tasks are woken.
+    pub(crate) fn cancel(&self) {
+        if let Some(signal) = lock(&self.0).signal.take() {
+            signal.wake();
+        }
+    }
+}
+
+impl Drop for InputWatch {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+fn lock(state: &Mutex<WatchState>) -> MutexGuard<'_, WatchState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn watch_input(
+    state: &Mutex<WatchState>,
+    signal: &Arc<WakeSignal>,
+    wait: impl Fn(&WakeSignal) -> Result<bool>,
+) {
+    // Errors are reported to the waiting tasks when they poll again.
+    while !signal.is_set() && !wait(signal).unwrap_or(true) {}
+
+    let wakers = {
+        let mut state = lock(state);
+        // New thread may have been started after cancel.
+        if state
+            .signal
+            .as_ref()
+            .is_some_and(|s| Arc::ptr_eq(s, signal))
+        {
+            state.signal = None;
+        }
+        mem::take(&mut state.wakers)
+    };
+    for waker in wakers {
+        waker.wake();
+    }
+}
@@ -0,0 +1,49 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::{error::Result, raw::events::Event};
+
+use super::{AsyncWaitForIn, IoProvider, StdioProvider, Terminal};
+
+/// Asynchronous stream of terminal events. The events are parsed in the same
+/// way as with [`Terminal::read`].
+///
+/// The stream ends when eof is reached on the input. When the stream is
+/// dropped, waiting for input is cancelled with
+/// [`AsyncWaitForIn::cancel_poll`].
+pub struct EventStream<'a, T: IoProvider + AsyncWaitForIn = StdioProvider> {
+    term: &'a mut Terminal<T>,
+}
+
+impl<'a, T: IoProvider + AsyncWaitForIn> EventStream<'a, T> {
+    /// Create new event stream reading from the given terminal.
+    pub fn new(term: &'a mut Terminal<T>) -> Self {
+        Self { term }
+    }
+
+    /// Get the underlying terminal.
+    pub fn terminal(&mut self) -> &mut Terminal<T> {
+        self.term
+    }
+}
+
+impl<T: IoProvider + AsyncWaitForIn> Stream for EventStream<'_, T> {
+    type Item = Result<Event>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.get_mut().term.poll_read(cx)
+    }
+}
+
+impl<T: IoProvider + AsyncWaitForIn> Drop for EventStream<'_, T> {
+    fn drop(&mut self) {
+        self.term.io().cancel_poll();
+    }
+}
@@ -3,7 +3,9 @@ use base64::Engine;
 use crate::{codes, raw::events::csi::Csi};
 
 use super::{
-    Key, KeyCode, Modifiers, Status, TermAttr, mouse::Mouse, osc::Osc,
+    Key, KeyCode, Modifiers, Status, TermAttr,
+    mouse::{self, Mouse},
+    osc::Osc,
     state_change::StateChange,
 };
 
@@ -42,6 +44,12 @@ pub enum Event {
     FocusLost,
     /// The input state has changed.
     StateChange(StateChange),
+    /// Text was pasted. Produced by [`Terminal`] only if it reads pastes with
+    /// [`PasteMode::Collect`].
+    ///
+    /// [`Terminal`]: crate::raw::Terminal
+    /// [`PasteMode::Collect`]: super::PasteMode::Collect
+    Paste(String),
 }
 
 impl AmbigousEvent {
@@ -91,7 +99,7 @@ impl AmbigousEvent {
     /// Parse the code into event.
     pub fn from_code(code: &[u8]) -> Self {
         if (6..=9).contains(&code.len()) && code.starts_with(b"\x1b[M") {
-            return Self::mouse_code(code);
+            return Self::mouse_code(code, None);
         }
 
         std::str::from_utf8(code)
@@ -100,29 +108,69 @@ impl AmbigousEvent {
             .unwrap_or_else(|| Self::unknown(code))
     }
 
+    /// Parse the code into event. Mouse events are decoded with the given
+    /// mouse encoding. If the encoding is `None`, it is guessed.
+    pub fn from_code_mouse(
+        code: &[u8],
+        encoding: Option<mouse::Encoding>,
+    ) -> Self {
+        if (6..=9).contains(&code.len()) && code.starts_with(b"\x1b[M") {
+            return Self::mouse_code(
+                code,
+                encoding.map(|e| e == mouse::Encoding::Utf8),
+            );
+        }
+
+        let mut res = Self::from_code(code);
+        if let AnyEvent::Known(Event::Mouse(m)) = &mut res.event {
+            m.pixels = encoding.is_some_and(|e| e.is_pixels())
+                && code.starts_with(b"\x1b[<");
+        }
+        res
+    }
+
     /// Create verbatim key code.
     pub fn verbatim(c: char) -> Self {
         Self::key(Key::verbatim(c))
     }
 
-    fn mouse_code(code: &[u8]) -> Self {
-        if code.len() == 6 && code.starts_with(b"\x1b[M") {
-            return AmbigousEvent::mouse(Mouse::from_data(
-                code[3] as u32 - 32,
-                code[4] as usize - 32,
-                code[5] as usize - 32,
-                None,
-            ));
+    /// Create unambiguous paste event.
+    pub fn paste(s: impl Into<String>) -> Self {
+        Self::event(Event::Paste(s.into()))
+    }
+
+    /// Parse mouse event in the classic or UTF-8 encoding. In the classic
+    /// encoding each value is single byte, in the UTF-8 encoding the values
+    /// may also be two byte UTF-8 characters. If `utf8` is `None`, the
+    /// encoding is guessed.
+    fn mouse_code(code: &[u8], utf8: Option<bool>) -> Self {
+        let mut data = &code[3..];
+        let mut vals = [0; 3];
+        for v in &mut vals {
+            let (val, rest) = match data {
+                [a @ 0xC2..=0xDF, b @ 0x80..=0xBF, rest @ ..]
+                    if utf8 != Some(false) =>
+                {
+                    (((*a as u32 & 0x1F) << 6) | (*b as u32 & 0x3F), rest)
+                }
+                [a, rest @ ..] => (*a as u32, rest),
+                [] => return Self::unknown(code),
+            };
+            let Some(val) = val.checked_sub(32) else {
+                return Self::unknown(code);
+            };
+            *v = val;
+            data = rest;
         }
-        let utf_code = std::str::from_utf8(&code[3..])
-            .map(|s| s.chars().collect::<Box<[char]>>());
-        let Ok([s, x, y]) = utf_code.as_deref() else {
+
+        if !data.is_empty() {
             return Self::unknown(code);
-        };
+        }
+
         AmbigousEvent::mouse(Mouse::from_data(
-            *s as u32 - 32,
-            *x as usize - 32,
-            *y as usize - 32,
+            vals[0],
+            vals[1] as usize,
+            vals[2] as usize,
             None,
         ))
     }
@@ -262,6 +310,10 @@ impl AmbigousEvent {
             ("", [201], "~") => {
                 Some(Self::state_change(StateChange::BracketedPasteEnd))
             }
+            // Shift+Tab
+            ("", [], "Z") => {
+                Some(Self::key(Key::mcode(KeyCode::Tab, Modifiers::SHIFT)))
+            }
             // Possibly VT key press
             ("", _, "~") => Self::csi_vt(csi),
             // Possibly xterm key press
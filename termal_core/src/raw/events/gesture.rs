@@ -0,0 +1,300 @@
+use std::time::{Duration, Instant};
+
+use super::{
+    Event, Modifiers,
+    mouse::{self, Button, Mouse},
+};
+
+/// Gesture recognized from mouse events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// The button was clicked. `count` is `1` for single click, `2` for double
+    /// click and so on.
+    Click {
+        button: Button,
+        modifiers: Modifiers,
+        x: usize,
+        y: usize,
+        count: usize,
+    },
+    /// Mouse started moving with the button held.
+    DragStart {
+        button: Button,
+        modifiers: Modifiers,
+        origin: (usize, usize),
+        x: usize,
+        y: usize,
+    },
+    /// Mouse moved with the button held.
+    DragMove {
+        button: Button,
+        modifiers: Modifiers,
+        origin: (usize, usize),
+        x: usize,
+        y: usize,
+    },
+    /// The held button was released after drag.
+    DragEnd {
+        button: Button,
+        modifiers: Modifiers,
+        origin: (usize, usize),
+        x: usize,
+        y: usize,
+    },
+    /// Mouse wheel was scrolled. `event` is one of the scroll events and
+    /// `delta` is the accelerated amount of scroll.
+    Wheel {
+        event: mouse::Event,
+        modifiers: Modifiers,
+        x: usize,
+        y: usize,
+        delta: usize,
+    },
+}
+
+/// Configuration of gesture [`Recognizer`].
+#[derive(Debug, Clone, Copy)]
+pub struct RecognizerConf {
+    /// Maximum time between clicks for them to form double (or triple, ...)
+    /// click. 500 ms by default.
+    pub multi_click_interval: Duration,
+    /// Maximum number of clicks in one multi click. The count starts again
+    /// from 1 after this. 3 (triple click) by default.
+    pub max_click_count: usize,
+    /// Maximum time between wheel events for them to accelerate. 100 ms by
+    /// default.
+    pub wheel_interval: Duration,
+    /// Number of consecutive wheel events after which the wheel delta
+    /// increases by one. `0` disables the acceleration. 4 by default.
+    pub wheel_acceleration: usize,
+    /// Maximum wheel delta. 8 by default.
+    pub max_wheel_delta: usize,
+}
+
+/// Recognizes gestures such as click, double click, drag and accelerated
+/// wheel from mouse events.
+///
+/// Pass it the events from [`Terminal::read`] or the last event of
+/// [`TermRead`] with [`Recognizer::handle_event`]. The positions of mouse
+/// events should be in characters.
+///
+/// [`Terminal::read`]: crate::raw::Terminal::read
+/// [`TermRead`]: crate::raw::readers::TermRead
+#[derive(Debug, Clone, Default)]
+pub struct Recognizer {
+    conf: RecognizerConf,
+    pressed: Option<Press>,
+    last_click: Option<LastClick>,
+    wheel: Option<WheelStreak>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    button: Button,
+    modifiers: Modifiers,
+    origin: (usize, usize),
+    dragging: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LastClick {
+    button: Button,
+    pos: (usize, usize),
+    time: Instant,
+    count: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WheelStreak {
+    event: mouse::Event,
+    time: Instant,
+    count: usize,
+}
+
+impl Recognizer {
+    /// Create new recognizer with the given configuration.
+    pub fn from_config(conf: RecognizerConf) -> Self {
+        Self {
+            conf,
+            ..Default::default()
+        }
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> &RecognizerConf {
+        &self.conf
+    }
+
+    /// Change the configuration.
+    pub fn config_mut(&mut self) -> &mut RecognizerConf {
+        &mut self.conf
+    }
+
+    /// Forget the state of all ongoing gestures.
+    pub fn reset(&mut self) {
+        self.pressed = None;
+        self.last_click = None;
+        self.wheel = None;
+    }
+
+    /// Handle the event. Returns gesture if the event completes one. Events
+    /// other than mouse events are ignored.
+    pub fn handle_event(&mut self, evt: &Event) -> Option<Gesture> {
+        match evt {
+            Event::Mouse(m) => self.handle_mouse(m),
+            _ => None,
+        }
+    }
+
+    /// Handle mouse event. Returns gesture if the event completes one.
+    pub fn handle_mouse(&mut self, mouse: &Mouse) -> Option<Gesture> {
+        self.handle_mouse_at(mouse, Instant::now())
+    }
+
+    /// Handle mouse event that has occured at the given time. Returns gesture
+    /// if the event completes one.
+    pub fn handle_mouse_at(
+        &mut self,
+        mouse: &Mouse,
+        time: Instant,
+    ) -> Option<Gesture> {
+        let pos = (mouse.x, mouse.y);
+        match mouse.event {
+            mouse::Event::Down => {
+                self.pressed = Some(Press {
+                    button: mouse.button,
+                    modifiers: mouse.modifiers,
+                    origin: pos,
+                    dragging: false,
+                });
+                None
+            }
+            mouse::Event::Drag => self.drag(mouse),
+            mouse::Event::Up => self.release(mouse, time),
+            mouse::Event::ScrollUp
+            | mouse::Event::ScrollDown
+            | mouse::Event::ScrollLeft
+            | mouse::Event::ScrollRight => Some(self.wheel(mouse, time)),
+            mouse::Event::Move => None,
+        }
+    }
+
+    fn drag(&mut self, mouse: &Mouse) -> Option<Gesture> {
+        let pos = (mouse.x, mouse.y);
+        // The press may have been missed. Start the drag here.
+        let press = self.pressed.get_or_insert(Press {
+            button: mouse.button,
+            modifiers: mouse.modifiers,
+            origin: pos,
+            dragging: false,
+        });
+
+        if press.dragging {
+            Some(Gesture::DragMove {
+                button: press.button,
+                modifiers: mouse.modifiers,
+                origin: press.origin,
+                x: mouse.x,
+                y: mouse.y,
+            })
+        } else if press.origin != pos {
+            press.dragging = true;
+            Some(Gesture::DragStart {
+                button: press.button,
+                modifiers: mouse.modifiers,
+                origin: press.origin,
+                x: mouse.x,
+                y: mouse.y,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn release(&mut self, mouse: &Mouse, time: Instant) -> Option<Gesture> {
+        // The classic mouse encoding doesn't say which button was released.
+        let press = self.pressed.take()?;
+
+        if press.dragging {
+            return Some(Gesture::DragEnd {
+                button: press.button,
+                modifiers: mouse.modifiers,
+                origin: press.origin,
+                x: mouse.x,
+                y: mouse.y,
+            });
+        }
+
+        let count = match self.last_click {
+            Some(c)
+                if c.button == press.button
+                    && c.pos == press.origin
+                    && c.count < self.conf.max_click_count
+                    && time.saturating_duration_since(c.time)
+                        <= self.conf.multi_click_interval =>
+            {
+                c.count + 1
+            }
+            _ => 1,
+        };
+
+        self.last_click = Some(LastClick {
+            button: press.button,
+            pos: press.origin,
+            time,
+            count,
+        });
+
+        Some(Gesture::Click {
+            button: press.button,
+            modifiers: press.modifiers,
+            x: press.origin.0,
+            y: press.origin.1,
+            count,
+        })
+    }
+
+    fn wheel(&mut self, mouse: &Mouse, time: Instant) -> Gesture {
+        let count = match self.wheel {
+            Some(w)
+                if w.event == mouse.event
+                    && time.saturating_duration_since(w.time)
+                        <= self.conf.wheel_interval =>
+            {
+                w.count + 1
+            }
+            _ => 0,
+        };
+
+        self.wheel = Some(WheelStreak {
+            event: mouse.event,
+            time,
+            count,
+        });
+
+        let delta = count
+            .checked_div(self.conf.wheel_acceleration)
+            .map_or(1, |d| d + 1)
+            .min(self.conf.max_wheel_delta.max(1));
+
+        Gesture::Wheel {
+            event: mouse.event,
+            modifiers: mouse.modifiers,
+            x: mouse.x,
+            y: mouse.y,
+            delta,
+        }
+    }
+}
+
+impl Default for RecognizerConf {
+    fn default() -> Self {
+        Self {
+            multi_click_interval: Duration::from_millis(500),
+            max_click_count: 3,
+            wheel_interval: Duration::from_millis(100),
+            wheel_acceleration: 4,
+            max_wheel_delta: 8,
+        }
+    }
+}
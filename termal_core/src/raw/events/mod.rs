@@ -1,10 +1,14 @@
 mod csi;
 mod event;
+pub mod gesture;
 mod key;
 pub mod mouse;
 mod osc;
+mod paste_mode;
 mod state_change;
 mod status;
 mod term_attr;
 
-pub use self::{event::*, key::*, state_change::*, status::*, term_attr::*};
+pub use self::{
+    event::*, key::*, paste_mode::*, state_change::*, status::*, term_attr::*,
+};
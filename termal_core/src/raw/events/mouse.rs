@@ -1,4 +1,6 @@
-use super::Modifiers;
+use crate::{codes, raw::TermSize};
+
+use super::{Modifiers, Status};
 
 bitflags::bitflags! {
     #[doc = "Key modifiers. Some of them are usualy not sent to terminals."]
@@ -14,30 +16,61 @@ bitflags::bitflags! {
         const CONTROL = 0x10;
         const MODIFIERS = 0x1C;
         const MOVE = 0x20;
+        const WHEEL = 0x40;
+        const EXTRA = 0x80;
+        const BUTTON_GROUP = 0xC0;
         const SCROLL_UP = 0x40;
         const SCROLL_DOWN = 0x41;
-        const SCROLL = 0x41;
-        const ACTION = 0x43;
+        const SCROLL_LEFT = 0x42;
+        const SCROLL_RIGHT = 0x43;
+        const BACK = 0x80;
+        const FORWARD = 0x81;
+        const BUTTON10 = 0x82;
+        const BUTTON11 = 0x83;
     }
 }
 
 /// Mouse button.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Button {
+    /// No button or the button is not known (e.g. release in the classic
+    /// encoding).
     None,
+    /// Button 1.
     Left,
+    /// Button 2.
     Middle,
+    /// Button 3.
     Right,
+    /// Button 8, usually the back button.
+    Back,
+    /// Button 9, usually the forward button.
+    Forward,
+    /// Button 10.
+    Button10,
+    /// Button 11.
+    Button11,
 }
 
 /// Mouse events.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Event {
+    /// The button was pressed.
     Down,
+    /// The button was released.
     Up,
+    /// Mouse wheel up (button 4).
     ScrollUp,
+    /// Mouse wheel down (button 5).
     ScrollDown,
+    /// Mouse wheel left (button 6).
+    ScrollLeft,
+    /// Mouse wheel right (button 7).
+    ScrollRight,
+    /// Mouse moved without any button pressed.
     Move,
+    /// Mouse moved with the button held.
+    Drag,
 }
 
 /// Mouse event.
@@ -55,6 +88,26 @@ pub struct Mouse {
     /// Y coordinate of mouse (may be either in chars on pixels depending on
     /// mouse mode)
     pub y: usize,
+    /// If this is `true`, the coordinates are in pixels. Otherwise they are in
+    /// characters.
+    pub pixels: bool,
+}
+
+/// Encoding of mouse events sent by the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// The classic encoding. Each value is single byte.
+    #[default]
+    Classic,
+    /// The UTF-8 extension. Values are encoded as UTF-8 characters.
+    Utf8,
+    /// The SGR extension. Position is in characters.
+    Sgr,
+    /// The URXVT extension.
+    Urxvt,
+    /// The SGR-Pixels extension. Same as [`Encoding::Sgr`], but position is
+    /// in pixels.
+    SgrPixels,
 }
 
 impl Mouse {
@@ -66,21 +119,29 @@ impl Mouse {
         down: Option<bool>,
     ) -> Self {
         let state = State::from_bits_retain(state);
-        let button = if state.contains(State::SCROLL) {
-            Button::None
+        let (button, event) = if (state & State::BUTTON_GROUP) == State::WHEEL
+        {
+            let event = match state & (State::BUTTON_GROUP | State::BUTTON) {
+                State::SCROLL_UP => Event::ScrollUp,
+                State::SCROLL_DOWN => Event::ScrollDown,
+                State::SCROLL_LEFT => Event::ScrollLeft,
+                _ => Event::ScrollRight,
+            };
+            (Button::None, event)
         } else {
-            state.into()
-        };
-        let event = if state.contains(State::MOVE) {
-            Event::Move
-        } else if (state & State::SCROLL) == State::SCROLL_UP {
-            Event::ScrollUp
-        } else if (state & State::SCROLL) == State::SCROLL_DOWN {
-            Event::ScrollDown
-        } else if down.unwrap_or(button != Button::None) {
-            Event::Down
-        } else {
-            Event::Up
+            let button = state.into();
+            let event = if state.contains(State::MOVE) {
+                if button == Button::None {
+                    Event::Move
+                } else {
+                    Event::Drag
+                }
+            } else if down.unwrap_or(button != Button::None) {
+                Event::Down
+            } else {
+                Event::Up
+            };
+            (button, event)
         };
         let modifiers = state.into();
 
@@ -90,18 +151,94 @@ impl Mouse {
             modifiers,
             x,
             y,
+            pixels: false,
         }
     }
+
+    /// Convert the position in pixels to position in characters with the given
+    /// size of single character in pixels. If the position is already in
+    /// characters, it is not changed.
+    pub fn to_cells(mut self, char_width: usize, char_height: usize) -> Self {
+        if !self.pixels {
+            return self;
+        }
+
+        // Both the positions in pixels and in characters start at 1.
+        self.x = self.x.saturating_sub(1) / char_width.max(1) + 1;
+        self.y = self.y.saturating_sub(1) / char_height.max(1) + 1;
+        self.pixels = false;
+        self
+    }
+
+    /// Convert the position in pixels to position in characters with the size
+    /// of character from [`Status::CharSize`]. Returns `None` if the status is
+    /// not [`Status::CharSize`].
+    pub fn to_cells_by_char_size(self, char_size: &Status) -> Option<Self> {
+        match char_size {
+            Status::CharSize { w, h } => Some(self.to_cells(*w, *h)),
+            _ => None,
+        }
+    }
+
+    /// Convert the position in pixels to position in characters with the size
+    /// of character computed from the terminal size. Returns `None` if the
+    /// size doesn't contain size in pixels.
+    pub fn to_cells_by_term_size(self, size: &TermSize) -> Option<Self> {
+        if size.pixel_width == 0
+            || size.pixel_height == 0
+            || size.char_width == 0
+            || size.char_height == 0
+        {
+            return None;
+        }
+
+        Some(self.to_cells(
+            size.pixel_width / size.char_width,
+            size.pixel_height / size.char_height,
+        ))
+    }
+}
+
+impl Encoding {
+    /// Get the code that enables this encoding.
+    pub fn enable_code(&self) -> &'static str {
+        match self {
+            Self::Classic => "",
+            Self::Utf8 => codes::ENABLE_MOUSE_XY_UTF8_EXT,
+            Self::Sgr => codes::ENABLE_MOUSE_XY_EXT,
+            Self::Urxvt => codes::ENABLE_MOUSE_XY_URXVT_EXT,
+            Self::SgrPixels => codes::ENABLE_MOUSE_XY_PIX_EXT,
+        }
+    }
+
+    /// Get the code that disables this encoding.
+    pub fn disable_code(&self) -> &'static str {
+        match self {
+            Self::Classic => "",
+            Self::Utf8 => codes::DISABLE_MOUSE_XY_UTF8_EXT,
+            Self::Sgr => codes::DISABLE_MOUSE_XY_EXT,
+            Self::Urxvt => codes::DISABLE_MOUSE_XY_URXVT_EXT,
+            Self::SgrPixels => codes::DISABLE_MOUSE_XY_PIX_EXT,
+        }
+    }
+
+    /// Checks whether the mouse position is in pixels with this encoding.
+    pub fn is_pixels(&self) -> bool {
+        *self == Self::SgrPixels
+    }
 }
 
 impl From<State> for Button {
     fn from(value: State) -> Self {
-        match value & State::BUTTON {
-            State::RELEASE => Self::None,
+        match value & (State::BUTTON_GROUP | State::BUTTON) {
             State::PRIMARY => Self::Left,
             State::MIDDLE => Self::Middle,
             State::SECONDARY => Self::Right,
-            _ => unreachable!(),
+            State::BACK => Self::Back,
+            State::FORWARD => Self::Forward,
+            State::BUTTON10 => Self::Button10,
+            State::BUTTON11 => Self::Button11,
+            _ => Self::None,
         }
     }
 }
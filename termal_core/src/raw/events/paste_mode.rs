@@ -0,0 +1,37 @@
+/// Determines how bracketed paste is read by [`Terminal`].
+///
+/// [`Terminal`]: crate::raw::Terminal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PasteMode {
+    /// The paste is surrounded with [`StateChange::BracketedPasteStart`] and
+    /// [`StateChange::BracketedPasteEnd`] and each character of the paste is
+    /// single verbatim key event.
+    ///
+    /// [`StateChange::BracketedPasteStart`]:
+    ///     super::StateChange::BracketedPasteStart
+    /// [`StateChange::BracketedPasteEnd`]:
+    ///     super::StateChange::BracketedPasteEnd
+    #[default]
+    Chars,
+    /// The whole paste is collected into single [`Event::Paste`].
+    ///
+    /// If the paste is longer than `max_len` bytes, it is streamed instead:
+    /// it is surrounded with [`StateChange::BracketedPasteStart`] and
+    /// [`StateChange::BracketedPasteEnd`] and the text is split into multiple
+    /// [`Event::Paste`] with `max_len` bytes each (rounded up to whole
+    /// characters).
+    ///
+    /// [`Event::Paste`]: super::Event::Paste
+    /// [`StateChange::BracketedPasteStart`]:
+    ///     super::StateChange::BracketedPasteStart
+    /// [`StateChange::BracketedPasteEnd`]:
+    ///     super::StateChange::BracketedPasteEnd
+    Collect { max_len: usize },
+}
+
+impl PasteMode {
+    /// Collect pastes with at most 1 MiB into single event.
+    pub const COLLECT: Self = Self::Collect {
+        max_len: 1024 * 1024,
+    };
+}
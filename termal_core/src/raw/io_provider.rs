@@ -3,7 +3,9 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
-use super::WaitForIn;
+use crate::error::Result;
+
+use super::{TermSize, WaitForIn, term_size};
 
 /// Represents mutable value that is either owned or borrowed.
 pub enum ValueOrMut<'a, T> {
@@ -36,6 +38,12 @@ pub trait IoProvider: WaitForIn {
     fn is_out_raw(&self) -> bool {
         false
     }
+
+    /// Gets the size of the terminal. By default this is the size of the
+    /// terminal of this process.
+    fn term_size(&self) -> Result<TermSize> {
+        term_size()
+    }
 }
 
 impl<T> AsRef<T> for ValueOrMut<'_, T> {
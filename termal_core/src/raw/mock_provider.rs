@@ -0,0 +1,357 @@
+use std::{borrow::Cow, io, time::Duration};
+
+use base64::Engine;
+
+use crate::{codes, error::Result};
+
+use super::{
+    IoProvider, TermSize, TestInput, ValueOrMut, WaitForIn,
+    events::{
+        Key, KeyCode, Modifiers, Status, TermFeatures, TermType,
+        mouse::{self, Button, Mouse},
+    },
+};
+
+/// Mock [`IoProvider`] for testing input handling. Input is built from
+/// events that are encoded to the sequences that terminal would send. Output
+/// is captured.
+///
+/// Each event is separate chunk of input. See [`TestInput`] for the meaning
+/// of gaps.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+///
+/// use termal_core::raw::{
+///     MockProvider, Terminal,
+///     events::{Event, Key, KeyCode},
+/// };
+///
+/// let mut term = Terminal::new(
+///     MockProvider::new()
+///         .key(Key::code(KeyCode::Up))
+///         .gap(Duration::from_millis(100))
+///         .text("a"),
+/// );
+///
+/// let evt = term.read_timeout(Duration::from_millis(10)).unwrap();
+/// assert_eq!(evt, Some(Event::KeyPress(Key::code(KeyCode::Up))));
+/// assert_eq!(term.read_timeout(Duration::from_millis(10)).unwrap(), None);
+/// assert!(term.read_timeout(Duration::from_millis(100)).unwrap().is_some());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockProvider {
+    input: TestInput,
+    out: Vec<u8>,
+    size: Option<(usize, usize)>,
+}
+
+impl MockProvider {
+    /// Create mock provider without any input.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the size of the terminal in characters. If not set, getting the
+    /// size fails.
+    pub fn size(mut self, width: usize, height: usize) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    /// Add raw chunk of input.
+    pub fn bytes(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.input.push(data);
+        self
+    }
+
+    /// Add text as single chunk of input.
+    pub fn text(self, s: impl AsRef<str>) -> Self {
+        self.bytes(s.as_ref())
+    }
+
+    /// Add key press.
+    pub fn key(self, key: Key) -> Self {
+        self.bytes(encode_key(&key))
+    }
+
+    /// Add key presses. Each key press is separate chunk.
+    pub fn keys(self, keys: impl IntoIterator<Item = Key>) -> Self {
+        keys.into_iter().fold(self, |s, k| s.key(k))
+    }
+
+    /// Add mouse event. It is encoded with the SGR extension
+    /// ([`mouse::Encoding::Sgr`]). If [`Mouse::pixels`] is set, it is encoded
+    /// as with [`mouse::Encoding::SgrPixels`].
+    ///
+    /// Both extensions send the same sequence, so the terminal must know the
+    /// encoding to decode the event with the correct unit. Use
+    /// [`super::Terminal::set_mouse_encoding`] to read events in pixels.
+    pub fn mouse(self, mouse: Mouse) -> Self {
+        self.bytes(encode_mouse(&mouse))
+    }
+
+    /// Add bracketed paste of the given text.
+    pub fn paste(self, s: impl AsRef<str>) -> Self {
+        self.bytes(format!(
+            "{}{}{}",
+            codes::BRACKETED_PASTE_START,
+            s.as_ref(),
+            codes::BRACKETED_PASTE_END
+        ))
+    }
+
+    /// Add status reply of the terminal.
+    pub fn status(self, status: &Status) -> Self {
+        self.bytes(encode_status(status))
+    }
+
+    /// Add gap before the next input.
+    pub fn gap(mut self, gap: Duration) -> Self {
+        self.input.push_gap(gap);
+        self
+    }
+
+    /// Checks whether there is any remaining input.
+    pub fn has_input(&self) -> bool {
+        self.input.has_data()
+    }
+
+    /// Get the captured output.
+    pub fn output(&self) -> &[u8] {
+        &self.out
+    }
+
+    /// Get the captured output as string.
+    pub fn output_str(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.out)
+    }
+
+    /// Take the captured output. The output is cleared.
+    pub fn take_output(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.out)
+    }
+}
+
+impl WaitForIn for MockProvider {
+    fn wait_for_in(&self, timeout: Duration) -> Result<bool> {
+        self.input.wait_for_in(timeout)
+    }
+}
+
+#[cfg(feature = "async")]
+impl super::AsyncWaitForIn for MockProvider {
+    fn poll_for_in(
+        &self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<()>> {
+        super::AsyncWaitForIn::poll_for_in(&self.input, cx)
+    }
+}
+
+impl IoProvider for MockProvider {
+    type Out = Vec<u8>;
+    type In = TestInput;
+
+    fn get_out(&mut self) -> ValueOrMut<'_, Self::Out> {
+        ValueOrMut::Mut(&mut self.out)
+    }
+
+    fn get_in(&mut self) -> ValueOrMut<'_, Self::In> {
+        ValueOrMut::Mut(&mut self.input)
+    }
+
+    fn is_out_terminal(&self) -> bool {
+        true
+    }
+
+    fn is_in_terminal(&self) -> bool {
+        true
+    }
+
+    fn is_out_raw(&self) -> bool {
+        true
+    }
+
+    fn term_size(&self) -> Result<TermSize> {
+        let (w, h) = self.size.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Size of the mock terminal is not set.",
+            )
+        })?;
+        Ok(TermSize {
+            char_width: w,
+            char_height: h,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+    }
+}
+
+fn encode_key(key: &Key) -> String {
+    let mods = key.modifiers;
+    let alt = if mods.contains(Modifiers::ALT) {
+        "\x1b"
+    } else {
+        ""
+    };
+    let ctrl = mods.contains(Modifiers::CONTROL);
+    // Modifier id used in escape sequences.
+    let m = mods.bits() + 1;
+
+    let (csi, ss3, vt) = match key.code {
+        KeyCode::Char(c) => {
+            let c = if ctrl {
+                match c.to_ascii_lowercase() {
+                    c @ ('a'..='z' | '[' | '\\' | ']' | '^' | '_') => {
+                        ((c.to_ascii_uppercase() as u8) & 0x1f) as char
+                    }
+                    '@' | ' ' => '\0',
+                    c => c,
+                }
+            } else if mods.contains(Modifiers::SHIFT) {
+                key.key_char.unwrap_or(c.to_ascii_uppercase())
+            } else {
+                key.key_char.unwrap_or(c)
+            };
+            return format!("{alt}{c}");
+        }
+        KeyCode::Space if ctrl => return format!("{alt}\0"),
+        KeyCode::Space => return format!("{alt} "),
+        KeyCode::Tab if mods.contains(Modifiers::SHIFT) => {
+            return "\x1b[Z".into();
+        }
+        KeyCode::Tab => return format!("{alt}\t"),
+        KeyCode::Enter => return format!("{alt}\r"),
+        KeyCode::Backspace if ctrl => return format!("{alt}\x08"),
+        KeyCode::Backspace => return format!("{alt}\x7f"),
+        KeyCode::Esc => return format!("{alt}\x1b"),
+        KeyCode::Up => ('A', false, 0),
+        KeyCode::Down => ('B', false, 0),
+        KeyCode::Right => ('C', false, 0),
+        KeyCode::Left => ('D', false, 0),
+        KeyCode::End => ('F', false, 0),
+        KeyCode::Home => ('H', false, 0),
+        KeyCode::F1 => ('P', true, 0),
+        KeyCode::F2 => ('Q', true, 0),
+        KeyCode::F3 => ('R', true, 0),
+        KeyCode::F4 => ('S', true, 0),
+        KeyCode::Insert => ('~', false, 2),
+        KeyCode::Delete => ('~', false, 3),
+        KeyCode::PgUp => ('~', false, 5),
+        KeyCode::PgDown => ('~', false, 6),
+        KeyCode::F0 => ('~', false, 10),
+        KeyCode::F5 => ('~', false, 15),
+        KeyCode::F6 => ('~', false, 17),
+        KeyCode::F7 => ('~', false, 18),
+        KeyCode::F8 => ('~', false, 19),
+        KeyCode::F9 => ('~', false, 20),
+        KeyCode::F10 => ('~', false, 21),
+        KeyCode::F11 => ('~', false, 23),
+        KeyCode::F12 => ('~', false, 24),
+        KeyCode::F13 => ('~', false, 25),
+        KeyCode::F14 => ('~', false, 26),
+        KeyCode::F15 => ('~', false, 28),
+        KeyCode::F16 => ('~', false, 29),
+        KeyCode::F17 => ('~', false, 31),
+        KeyCode::F18 => ('~', false, 32),
+        KeyCode::F19 => ('~', false, 33),
+        KeyCode::F20 => ('~', false, 34),
+    };
+
+    match (vt, mods.is_empty()) {
+        (0, true) if ss3 => format!("\x1bO{csi}"),
+        (0, true) => format!("\x1b[{csi}"),
+        (0, false) => format!("\x1b[1;{m}{csi}"),
+        (vt, true) => format!("\x1b[{vt}~"),
+        (vt, false) => format!("\x1b[{vt};{m}~"),
+    }
+}
+
+fn encode_mouse(mouse: &Mouse) -> String {
+    let button = match mouse.button {
+        Button::Left => 0,
+        Button::Middle => 1,
+        Button::Right => 2,
+        Button::None => 3,
+        Button::Back => 0x80,
+        Button::Forward => 0x81,
+        Button::Button10 => 0x82,
+        Button::Button11 => 0x83,
+    };
+    let (state, down) = match mouse.event {
+        mouse::Event::Down => (button, true),
+        mouse::Event::Up => (button, false),
+        mouse::Event::ScrollUp => (0x40, true),
+        mouse::Event::ScrollDown => (0x41, true),
+        mouse::Event::ScrollLeft => (0x42, true),
+        mouse::Event::ScrollRight => (0x43, true),
+        mouse::Event::Move => (0x23, true),
+        mouse::Event::Drag => (0x20 | button, true),
+    };
+    let mods = mouse.modifiers;
+    let mods = (mods
+        & (Modifiers::SHIFT | Modifiers::ALT | Modifiers::CONTROL))
+        .bits()
+        << 2;
+
+    format!(
+        "\x1b[<{};{};{}{}",
+        state | mods,
+        mouse.x,
+        mouse.y,
+        if down { 'M' } else { 'm' }
+    )
+}
+
+fn encode_status(status: &Status) -> String {
+    let osc = |args: &str| format!("{}{args}{}", codes::OSC, codes::ST);
+
+    match status {
+        Status::Attributes(attr) => {
+            let mut args = match attr.typ {
+                TermType::Vt100 => "1;2".to_string(),
+                TermType::Vt101 => "1;0".into(),
+                TermType::Vt132 => "4;6".into(),
+                TermType::Vt102 => "6".into(),
+                TermType::Vt131 => "7".into(),
+                TermType::Vt125 => "12".into(),
+                TermType::Vt220 => "62".into(),
+                TermType::Vt320 => "63".into(),
+                TermType::Vt420 => "64".into(),
+                TermType::Vt510 => "65".into(),
+                TermType::Other(Some(i)) => i.to_string(),
+                TermType::Other(None) => String::new(),
+            };
+            for id in 1..32 {
+                let f = TermFeatures::from_id(id);
+                if f != TermFeatures::NONE && attr.features.contains(f) {
+                    args += &format!(";{id}");
+                }
+            }
+            format!("\x1b[?{args}c")
+        }
+        Status::Ok => "\x1b[0n".into(),
+        Status::CursorPosition { x, y } => format!("\x1b[{y};{x}R"),
+        Status::TerminalName(name) => {
+            format!("{}>|{name}{}", codes::DCS, codes::ST)
+        }
+        Status::TextAreaSizePx { w, h } => format!("\x1b[4;{h};{w}t"),
+        Status::TextAreaSize { w, h } => format!("\x1b[8;{h};{w}t"),
+        Status::CharSize { w, h } => format!("\x1b[6;{h};{w}t"),
+        Status::SixelColors(c) => format!("\x1b[?1;0;{c}S"),
+        Status::SixelSize { w, h } => format!("\x1b[?2;0;{w};{h}S"),
+        Status::ColorCodeColor { code, color } => {
+            osc(&format!("4;{code};{color}"))
+        }
+        Status::DefaultFgColor(c) => osc(&format!("10;{c}")),
+        Status::DefaultBgColor(c) => osc(&format!("11;{c}")),
+        Status::CursorColor(c) => osc(&format!("12;{c}")),
+        Status::SelectionData(data) => osc(&format!(
+            "52;c;{}",
+            base64::prelude::BASE64_STANDARD.encode(data)
+        )),
+    }
+}
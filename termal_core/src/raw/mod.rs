@@ -3,11 +3,39 @@ mod stdio_provider;
 mod sys;
 mod terminal;
 mod wait_for_in;
+mod wake_signal;
 
 pub use self::{
     io_provider::*, stdio_provider::*, sys::*, terminal::*, wait_for_in::*,
+    wake_signal::*,
 };
 
+#[cfg(feature = "async")]
+mod async_wait_for_in;
+#[cfg(feature = "async")]
+mod event_stream;
+#[cfg(all(feature = "testing", feature = "events"))]
+mod mock_provider;
+#[cfg(feature = "events")]
+mod reader_thread;
+#[cfg(feature = "testing")]
+mod test_input;
+#[cfg(feature = "testing")]
+mod test_terminal;
+#[cfg(unix)]
+mod tty_provider;
+
+#[cfg(all(feature = "testing", feature = "events"))]
+pub use self::mock_provider::*;
+#[cfg(feature = "events")]
+pub use self::reader_thread::*;
+#[cfg(unix)]
+pub use self::tty_provider::*;
+#[cfg(feature = "async")]
+pub use self::{async_wait_for_in::*, event_stream::*};
+#[cfg(feature = "testing")]
+pub use self::{test_input::*, test_terminal::*};
+
 #[cfg(feature = "events")]
 pub mod events;
 #[cfg(feature = "readers")]
@@ -0,0 +1,193 @@
+use std::{
+    collections::VecDeque,
+    io::Write,
+    panic,
+    sync::{
+        Arc, Mutex, MutexGuard,
+        mpsc::{self, Receiver, SendError, Sender},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    error::{Error, Result},
+    raw::events::{AmbigousEvent, AnyEvent, Event, Status},
+};
+
+use super::{InputWait, IoProvider, StdioProvider, Terminal, WakeSignal};
+
+/// Maximum duration of single wait for input on the reader thread.
+const WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+
+type StatusRequests = Mutex<VecDeque<Sender<Status>>>;
+
+/// Handle to thread that reads events from terminal in background. Created
+/// with [`Terminal::spawn_reader`].
+///
+/// Status events (replies to queries) are sent to the requesters registered
+/// with [`ReaderHandle::request_status`] or [`ReaderHandle::query`] in the
+/// order in which they were registered. Status events that nobody waits for
+/// are sent with the other events.
+///
+/// Dropping the handle will stop the thread, but it will not wait for it.
+pub struct ReaderHandle<T: IoProvider = StdioProvider> {
+    signal: Arc<WakeSignal>,
+    requests: Arc<StatusRequests>,
+    thread: Option<JoinHandle<(Terminal<T>, Result<()>)>>,
+}
+
+impl<T: IoProvider + Send + 'static> ReaderHandle<T> {
+    /// Spawn thread reading events from the given terminal. Returns receiver
+    /// of the events and handle to the thread.
+    pub fn spawn(mut term: Terminal<T>) -> Result<(Receiver<Event>, Self)> {
+        let signal = Arc::new(WakeSignal::new()?);
+        let requests = Arc::new(StatusRequests::default());
+        let (sender, receiver) = mpsc::channel();
+
+        let thread = {
+            let signal = signal.clone();
+            let requests = requests.clone();
+            thread::Builder::new().name("termal-reader".into()).spawn(
+                move || {
+                    let r = read_loop(&mut term, &signal, &requests, sender);
+                    (term, r)
+                },
+            )?
+        };
+
+        Ok((
+            receiver,
+            Self {
+                signal,
+                requests,
+                thread: Some(thread),
+            },
+        ))
+    }
+
+    /// Stop the reader thread and wait for it to finish. Returns the terminal
+    /// or the error that has stopped the thread.
+    pub fn stop(mut self) -> Result<Terminal<T>> {
+        self.signal.wake();
+        let (term, r) = self
+            .thread
+            .take()
+            .unwrap()
+            .join()
+            .unwrap_or_else(|e| panic::resume_unwind(e));
+        r.map(|_| term)
+    }
+}
+
+impl<T: IoProvider> ReaderHandle<T> {
+    /// Register for the next status event that is not taken by previously
+    /// registered requester.
+    pub fn request_status(&self) -> Receiver<Status> {
+        let (sender, receiver) = mpsc::channel();
+        get_requests(&self.requests).push_back(sender);
+        receiver
+    }
+
+    /// Write the query to `out` and wait for the reply from the terminal.
+    /// Block for at most the given duration. Returns `None` if the terminal
+    /// didn't reply in time.
+    pub fn query(
+        &self,
+        out: &mut impl Write,
+        query: impl AsRef<str>,
+        timeout: Duration,
+    ) -> Result<Option<Status>> {
+        let receiver = self.request_status();
+        out.write_all(query.as_ref().as_bytes())?;
+        out.flush()?;
+        Ok(receiver.recv_timeout(timeout).ok())
+    }
+
+    /// Checks whether the reader thread has finished. This may be because of
+    /// eof, error, or because all receivers of events have been dropped.
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(|t| t.is_finished())
+    }
+}
+
+impl<T: IoProvider> Drop for ReaderHandle<T> {
+    fn drop(&mut self) {
+        self.signal.wake();
+    }
+}
+
+fn read_loop<T: IoProvider>(
+    term: &mut Terminal<T>,
+    signal: &Arc<WakeSignal>,
+    requests: &StatusRequests,
+    events: Sender<Event>,
+) -> Result<()> {
+    while !signal.is_set() {
+        if !term.wait_for_input_or(signal, WAIT_TIMEOUT)? {
+            continue;
+        }
+
+        // The signal is checked also while waiting for the rest of event.
+        let wait = InputWait::Signal(signal.clone());
+        let evt = match term.try_read_ambigous(wait) {
+            Ok(Some(e)) => e,
+            Ok(None) => continue,
+            Err(Error::StdInEof) => break,
+            Err(e) => return Err(e),
+        };
+
+        let Some(evt) = route_status(requests, evt) else {
+            continue;
+        };
+
+        if events.send(evt).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Send status to the waiting requester. Returns the event if it should be
+/// sent as normal event.
+fn route_status(
+    requests: &StatusRequests,
+    evt: AmbigousEvent,
+) -> Option<Event> {
+    let mut requests = get_requests(requests);
+
+    let (mut status, event) = match evt.event {
+        AnyEvent::Known(Event::Status(s)) => (s, None),
+        // Status may be ambiguous with other event (e.g. cursor position
+        // report and F3 with modifiers). Prefer the status if someone waits
+        // for it.
+        AnyEvent::Known(e) if !requests.is_empty() => {
+            let Some(s) = evt.other.into_iter().find_map(|e| match e {
+                Event::Status(s) => Some(s),
+                _ => None,
+            }) else {
+                return Some(e);
+            };
+            (s, Some(e))
+        }
+        AnyEvent::Known(e) => return Some(e),
+        AnyEvent::Unknown(_) => return None,
+    };
+
+    while let Some(req) = requests.pop_front() {
+        match req.send(status) {
+            Ok(_) => return None,
+            // The requester doesn't wait anymore.
+            Err(SendError(s)) => status = s,
+        }
+    }
+
+    Some(event.unwrap_or(Event::Status(status)))
+}
+
+fn get_requests(
+    requests: &StatusRequests,
+) -> MutexGuard<'_, VecDeque<Sender<Status>>> {
+    requests.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,84 @@
+use std::ops::Range;
+
+/// Candidate for completion returned by [`Completer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    /// Range of characters in the buffer that will be replaced.
+    pub range: Range<usize>,
+    /// Text that will replace the range.
+    pub replacement: String,
+    /// Text that is shown when listing the candidates. If `None`,
+    /// `replacement` is shown.
+    pub display: Option<String>,
+}
+
+/// Provides completions for [`TermRead`].
+///
+/// [`TermRead`]: super::TermRead
+pub trait Completer {
+    /// Get candidates for completion of the buffer with cursor at the given
+    /// position (index of char in `buf`).
+    fn complete(&mut self, buf: &[char], pos: usize) -> Vec<Candidate>;
+}
+
+impl<F> Completer for F
+where
+    F: FnMut(&[char], usize) -> Vec<Candidate>,
+{
+    fn complete(&mut self, buf: &[char], pos: usize) -> Vec<Candidate> {
+        self(buf, pos)
+    }
+}
+
+impl Candidate {
+    /// Create candidate that replaces the given range.
+    pub fn new(range: Range<usize>, replacement: impl Into<String>) -> Self {
+        Self {
+            range,
+            replacement: replacement.into(),
+            display: None,
+        }
+    }
+
+    /// Set the text that is shown when listing the candidates.
+    pub fn with_display(mut self, display: impl Into<String>) -> Self {
+        self.display = Some(display.into());
+        self
+    }
+
+    /// Get the text that is shown when listing the candidates.
+    pub fn display(&self) -> &str {
+        self.display.as_deref().unwrap_or(&self.replacement)
+    }
+}
+
+/// Completer that completes the word before cursor from the given list of
+/// words.
+#[derive(Debug, Clone, Default)]
+pub struct WordCompleter {
+    words: Vec<String>,
+}
+
+impl WordCompleter {
+    /// Create completer for the given words.
+    pub fn new<S: Into<String>>(words: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            words: words.into_iter().map(|w| w.into()).collect(),
+        }
+    }
+}
+
+impl Completer for WordCompleter {
+    fn complete(&mut self, buf: &[char], pos: usize) -> Vec<Candidate> {
+        let start = buf[..pos]
+            .iter()
+            .rposition(|c| c.is_whitespace())
+            .map_or(0, |p| p + 1);
+        let word: String = buf[start..pos].iter().collect();
+        self.words
+            .iter()
+            .filter(|w| w.starts_with(&word))
+            .map(|w| Candidate::new(start..pos, w.clone()))
+            .collect()
+    }
+}
@@ -0,0 +1,18 @@
+/// Decides which typed or pasted characters are inserted into the input of
+/// [`TermRead`].
+///
+/// [`TermRead`]: super::TermRead
+pub trait InputFilter {
+    /// Checks whether the char `c` may be inserted at the position `pos`
+    /// (index of char in `buf`).
+    fn accept(&self, buf: &[char], pos: usize, c: char) -> bool;
+}
+
+impl<F> InputFilter for F
+where
+    F: Fn(&[char], usize, char) -> bool,
+{
+    fn accept(&self, buf: &[char], pos: usize, c: char) -> bool {
+        self(buf, pos, c)
+    }
+}
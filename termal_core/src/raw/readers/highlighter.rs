@@ -0,0 +1,24 @@
+use crate::term_text::TermText;
+
+/// Styles the input of [`TermRead`] when it is printed.
+///
+/// [`TermRead`]: super::TermRead
+pub trait Highlighter {
+    /// Get styled version of the buffer. Only SGR sequences (e.g.
+    /// [`codes::BOLD`]) from the result are used. The displayed characters
+    /// are always taken from the buffer, so the result should contain the
+    /// same characters as the buffer.
+    ///
+    /// [`codes::BOLD`]: crate::codes::BOLD
+    fn highlight(&mut self, buf: &[char]) -> TermText<'static>;
+}
+
+impl<F, R> Highlighter for F
+where
+    F: FnMut(&[char]) -> R,
+    R: Into<TermText<'static>>,
+{
+    fn highlight(&mut self, buf: &[char]) -> TermText<'static> {
+        self(buf).into()
+    }
+}
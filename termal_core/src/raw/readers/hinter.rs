@@ -0,0 +1,19 @@
+/// Provides hints for [`TermRead`]. The hint is shown dimmed after the cursor
+/// when the cursor is at the end of the input. It may be accepted with the
+/// right arrow or end.
+///
+/// [`TermRead`]: super::TermRead
+pub trait Hinter {
+    /// Get hint for the buffer with cursor at the given position (index of
+    /// char in `buf`). The hint is text that would be appended to the buffer.
+    fn hint(&mut self, buf: &[char], pos: usize) -> Option<String>;
+}
+
+impl<F> Hinter for F
+where
+    F: FnMut(&[char], usize) -> Option<String>,
+{
+    fn hint(&mut self, buf: &[char], pos: usize) -> Option<String> {
+        self(buf, pos)
+    }
+}
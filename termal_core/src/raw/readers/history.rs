@@ -0,0 +1,224 @@
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::error::Result;
+
+/// Store of previously accepted inputs of [`TermRead`].
+///
+/// [`TermRead`]: super::TermRead
+pub trait History: Debug {
+    /// Get the number of entries in the history.
+    fn len(&self) -> usize;
+
+    /// Get entry in the history. `0` is the oldest entry.
+    fn get(&self, idx: usize) -> Option<&str>;
+
+    /// Add new accepted entry to the history.
+    fn add(&mut self, entry: &str) -> Result<()>;
+
+    /// Checks whether the history is empty.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// History stored in memory.
+#[derive(Debug, Clone)]
+pub struct MemHistory {
+    entries: VecDeque<String>,
+    max_len: usize,
+    dedup: bool,
+}
+
+/// History stored in memory and in file. Accepted entries are appended to the
+/// file. Duplicate entries are removed.
+#[derive(Debug)]
+pub struct FileHistory {
+    mem: MemHistory,
+    path: PathBuf,
+}
+
+impl MemHistory {
+    /// Create new empty history that keeps at most `max_len` newest entries.
+    /// Entry is not added if it is the same as the last entry.
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            max_len,
+            dedup: false,
+        }
+    }
+
+    /// If this is set, older entries that are the same as the added entry
+    /// are removed. Disabled by default.
+    pub fn set_dedup(&mut self, v: bool) {
+        self.dedup = v;
+    }
+
+    /// Get the maximum number of entries.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Iterate over the entries from the oldest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.entries.iter().map(|s| s.as_str())
+    }
+
+    /// Remove all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Add entry to the history. Returns `true` if it was added.
+    fn push(&mut self, entry: &str) -> bool {
+        if entry.is_empty() || self.entries.back().is_some_and(|e| e == entry)
+        {
+            return false;
+        }
+        if self.dedup {
+            self.entries.retain(|e| e != entry);
+        }
+        self.entries.push_back(entry.to_string());
+        while self.entries.len() > self.max_len {
+            self.entries.pop_front();
+        }
+        true
+    }
+}
+
+impl History for MemHistory {
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn get(&self, idx: usize) -> Option<&str> {
+        self.entries.get(idx).map(|s| s.as_str())
+    }
+
+    fn add(&mut self, entry: &str) -> Result<()> {
+        self.push(entry);
+        Ok(())
+    }
+}
+
+impl FileHistory {
+    /// Load history from the given file. The file is created when the first
+    /// entry is added. At most `max_len` newest entries are kept.
+    ///
+    /// If the file contains duplicate entries or more than `max_len`
+    /// entries, it is rewritten without them.
+    pub fn open(path: impl AsRef<Path>, max_len: usize) -> Result<Self> {
+        let path = path.as_ref().to_owned();
+        let mut mem = MemHistory::new(max_len);
+        mem.set_dedup(true);
+
+        let data = match fs::read_to_string(&path) {
+            Ok(d) => d,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut cnt = 0;
+        for line in data.lines() {
+            mem.push(&unescape(line));
+            cnt += 1;
+        }
+
+        let res = Self { mem, path };
+        if cnt != res.mem.len() {
+            res.rewrite()?;
+        }
+        Ok(res)
+    }
+
+    /// Get the path to the history file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the history in memory.
+    pub fn mem(&self) -> &MemHistory {
+        &self.mem
+    }
+
+    /// Remove all entries (also from the file).
+    pub fn clear(&mut self) -> Result<()> {
+        self.mem.clear();
+        self.rewrite()
+    }
+
+    fn rewrite(&self) -> Result<()> {
+        let mut f = BufWriter::new(File::create(&self.path)?);
+        for e in self.mem.iter() {
+            writeln!(f, "{}", escape(e))?;
+        }
+        f.flush()?;
+        Ok(())
+    }
+}
+
+impl History for FileHistory {
+    fn len(&self) -> usize {
+        self.mem.len()
+    }
+
+    fn get(&self, idx: usize) -> Option<&str> {
+        self.mem.get(idx)
+    }
+
+    fn add(&mut self, entry: &str) -> Result<()> {
+        let len = self.mem.len();
+        let dup = self.mem.iter().any(|e| e == entry);
+        if !self.mem.push(entry) {
+            return Ok(());
+        }
+        // Older entry was removed, so it must be removed also from the file.
+        if dup || self.mem.len() <= len {
+            return self.rewrite();
+        }
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(f, "{}", escape(entry))?;
+        Ok(())
+    }
+}
+
+/// Escape newlines and backslashes so that the entry is on single line.
+fn escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => res += "\\\\",
+            '\n' => res += "\\n",
+            c => res.push(c),
+        }
+    }
+    res
+}
+
+fn unescape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                res.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                res.push('\\');
+                chars.next();
+            }
+            (c, _) => res.push(c),
+        }
+    }
+    res
+}
@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use crate::raw::events::{Key, KeyCode, Modifiers};
+
+/// Editor command of [`TermRead`] that may be bound to key in [`Keymap`].
+///
+/// [`TermRead`]: super::TermRead
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Command {
+    /// Move one character left.
+    MoveLeft,
+    /// Move one character right. Accepts the hint at the end of the input.
+    MoveRight,
+    /// Move to the start of the previous word.
+    MoveWordLeft,
+    /// Move to the end of the next word.
+    MoveWordRight,
+    /// Move to the start of the line.
+    MoveLineStart,
+    /// Move to the end of the line. Accepts the hint at the end of the input.
+    MoveLineEnd,
+    /// Move to the previous line in multi-line mode, or show the previous
+    /// history entry.
+    MoveUp,
+    /// Move to the next line in multi-line mode, or show the next history
+    /// entry.
+    MoveDown,
+    /// Delete the character before the cursor.
+    Backspace,
+    /// Delete the character at the cursor.
+    Delete,
+    /// Kill (cut) text from the cursor to the end of the line.
+    KillLineEnd,
+    /// Kill (cut) text from the start of the line to the cursor.
+    KillLineStart,
+    /// Kill (cut) the word before the cursor.
+    KillWordLeft,
+    /// Kill (cut) the word after the cursor.
+    KillWordRight,
+    /// Insert the last killed text.
+    Yank,
+    /// Insert the last killed text after the character at the cursor.
+    YankAfter,
+    /// Replace the just yanked text with the previous killed text.
+    YankPop,
+    /// Swap the character before the cursor with the character at the
+    /// cursor.
+    TransposeChars,
+    /// Undo the last change. Consecutive typed characters are undone at
+    /// once.
+    Undo,
+    /// Redo the last undone change.
+    Redo,
+    /// Clear the screen and show the input at its top.
+    ClearScreen,
+    /// Complete the input or cycle to the next candidate.
+    Complete,
+    /// Cycle to the previous completion candidate.
+    CompleteBack,
+    /// Start incremental history search backwards, or go to the previous
+    /// match.
+    SearchBackward,
+    /// Start incremental history search forwards, or go to the next match.
+    SearchForward,
+    /// Request the clipboard contents from the terminal and insert it.
+    Paste,
+    /// Switch to vi normal mode.
+    NormalMode,
+    /// Switch to insert mode.
+    InsertMode,
+    /// Move one character right and switch to insert mode.
+    InsertAfter,
+    /// Move to the start of the line and switch to insert mode.
+    InsertLineStart,
+    /// Move to the end of the line and switch to insert mode.
+    InsertLineEnd,
+    /// Kill (cut) the text over which the next motion command moves. If it is
+    /// followed by itself, the whole line is killed.
+    DeleteMotion,
+    /// Same as [`Command::DeleteMotion`] but switch to insert mode after the
+    /// text is killed. Word motion kills only to the end of the word.
+    ChangeMotion,
+}
+
+/// Mode of the editor in [`TermRead`].
+///
+/// [`TermRead`]: super::TermRead
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EditMode {
+    /// Typed characters are inserted.
+    #[default]
+    Insert,
+    /// Vi normal mode. Typed characters are not inserted, only the commands
+    /// are executed.
+    Normal,
+}
+
+/// Maps keys to editor commands of [`TermRead`].
+///
+/// Keys are matched by their code and modifiers. Keys that are not bound
+/// insert their character in insert mode and are ignored in normal mode.
+/// Note that uppercase characters have the shift modifier and their code is
+/// lowercase.
+///
+/// [`TermRead`]: super::TermRead
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    insert: HashMap<(KeyCode, Modifiers), Command>,
+    /// `None` if there is no normal mode.
+    normal: Option<HashMap<(KeyCode, Modifiers), Command>>,
+}
+
+impl Keymap {
+    /// Create the default emacs like keymap. Same as [`Keymap::emacs`].
+    pub fn new() -> Self {
+        Self::emacs()
+    }
+
+    /// Create keymap without any bindings and without normal mode.
+    pub fn empty() -> Self {
+        Self {
+            insert: HashMap::new(),
+            normal: None,
+        }
+    }
+
+    /// Create the default emacs like keymap.
+    pub fn emacs() -> Self {
+        use Command::*;
+
+        let mut res = Self::empty();
+        res.bind_common();
+        res.bind_all([
+            (ctrl('b'), MoveLeft),
+            (ctrl('f'), MoveRight),
+            (alt('b'), MoveWordLeft),
+            (alt('f'), MoveWordRight),
+            (ctrl('a'), MoveLineStart),
+            (ctrl('e'), MoveLineEnd),
+            (ctrl('p'), MoveUp),
+            (ctrl('n'), MoveDown),
+            (ctrl('d'), Delete),
+            (ctrl('k'), KillLineEnd),
+            (alt('d'), KillWordRight),
+            (Key::mcode(KeyCode::Backspace, Modifiers::ALT), KillWordLeft),
+            (ctrl('y'), Yank),
+            (alt('y'), YankPop),
+            (ctrl('t'), TransposeChars),
+            (Key::code(KeyCode::Char('\x1f')), Undo),
+            (ctrl('_'), Undo),
+            (alt('/'), Redo),
+        ]);
+        res
+    }
+
+    /// Create vi like keymap. It starts in insert mode and esc switches to
+    /// normal mode.
+    ///
+    /// Normal mode has the basic motions (`h`, `l`, `w`, `b`, `0`, `^`, `$`,
+    /// `j`, `k`), `x`, `X`, `D`, `p`, `P`, `u`, `Ctrl+R`, switching to insert
+    /// mode (`i`, `a`, `I`, `A`) and the operators `d` and `c` with the
+    /// motions (e.g. `dw`, `cw`, `d$`, `dd` and `cc`). Counts, registers,
+    /// visual mode and other commands are not supported.
+    pub fn vi() -> Self {
+        use Command::*;
+
+        let mut res = Self::empty();
+        res.bind_common();
+        res.bind(Key::code(KeyCode::Esc), NormalMode);
+
+        res.bind_normal_all([
+            (Key::code(KeyCode::Left), MoveLeft),
+            (Key::code(KeyCode::Right), MoveRight),
+            (Key::code(KeyCode::Home), MoveLineStart),
+            (Key::code(KeyCode::End), MoveLineEnd),
+            (Key::code(KeyCode::Up), MoveUp),
+            (Key::code(KeyCode::Down), MoveDown),
+            (Key::code(KeyCode::Backspace), MoveLeft),
+            (Key::code(KeyCode::Delete), Delete),
+            (Key::code(KeyCode::Tab), Complete),
+            (Key::mcode(KeyCode::Tab, Modifiers::SHIFT), CompleteBack),
+            (chr('h'), MoveLeft),
+            (chr('l'), MoveRight),
+            (Key::code(KeyCode::Space), MoveRight),
+            (chr('b'), MoveWordLeft),
+            (chr('w'), MoveWordRight),
+            (chr('0'), MoveLineStart),
+            (chr('^'), MoveLineStart),
+            (chr('$'), MoveLineEnd),
+            (chr('k'), MoveUp),
+            (chr('j'), MoveDown),
+            (chr('x'), Delete),
+            (shift('x'), Backspace),
+            (shift('d'), KillLineEnd),
+            (chr('p'), YankAfter),
+            (shift('p'), Yank),
+            (chr('d'), DeleteMotion),
+            (chr('c'), ChangeMotion),
+            (chr('u'), Undo),
+            (ctrl('r'), Redo),
+            (chr('i'), InsertMode),
+            (chr('a'), InsertAfter),
+            (shift('i'), InsertLineStart),
+            (shift('a'), InsertLineEnd),
+            (chr('/'), SearchBackward),
+            (chr('?'), SearchForward),
+            (ctrl('l'), ClearScreen),
+        ]);
+        res
+    }
+
+    /// Bind the key to the command in insert mode.
+    pub fn bind(&mut self, key: Key, cmd: Command) {
+        self.insert.insert((key.code, key.modifiers), cmd);
+    }
+
+    /// Remove binding of the key in insert mode. Returns the command that was
+    /// bound to the key.
+    pub fn unbind(&mut self, key: Key) -> Option<Command> {
+        self.insert.remove(&(key.code, key.modifiers))
+    }
+
+    /// Bind the key to the command in normal mode. This enables the normal
+    /// mode.
+    pub fn bind_normal(&mut self, key: Key, cmd: Command) {
+        self.normal
+            .get_or_insert_default()
+            .insert((key.code, key.modifiers), cmd);
+    }
+
+    /// Remove binding of the key in normal mode. Returns the command that was
+    /// bound to the key.
+    pub fn unbind_normal(&mut self, key: Key) -> Option<Command> {
+        self.normal.as_mut()?.remove(&(key.code, key.modifiers))
+    }
+
+    /// Checks whether the keymap has normal mode.
+    pub fn has_normal_mode(&self) -> bool {
+        self.normal.is_some()
+    }
+
+    /// Get the command bound to the key in the given mode.
+    pub fn get(&self, key: &Key, mode: EditMode) -> Option<Command> {
+        let map = match mode {
+            EditMode::Insert => &self.insert,
+            EditMode::Normal => self.normal.as_ref()?,
+        };
+        map.get(&(key.code, key.modifiers)).copied()
+    }
+
+    fn bind_all(&mut self, binds: impl IntoIterator<Item = (Key, Command)>) {
+        for (k, c) in binds {
+            self.bind(k, c);
+        }
+    }
+
+    fn bind_normal_all(
+        &mut self,
+        binds: impl IntoIterator<Item = (Key, Command)>,
+    ) {
+        for (k, c) in binds {
+            self.bind_normal(k, c);
+        }
+    }
+
+    /// Bindings shared by emacs keymap and vi insert mode.
+    fn bind_common(&mut self) {
+        use Command::*;
+
+        self.bind_all([
+            (Key::code(KeyCode::Left), MoveLeft),
+            (Key::code(KeyCode::Right), MoveRight),
+            (Key::mcode(KeyCode::Left, Modifiers::CONTROL), MoveWordLeft),
+            (
+                Key::mcode(KeyCode::Right, Modifiers::CONTROL),
+                MoveWordRight,
+            ),
+            (Key::code(KeyCode::Home), MoveLineStart),
+            (Key::code(KeyCode::End), MoveLineEnd),
+            (Key::code(KeyCode::Up), MoveUp),
+            (Key::code(KeyCode::Down), MoveDown),
+            (Key::code(KeyCode::Backspace), Backspace),
+            (ctrl('h'), Backspace),
+            (Key::code(KeyCode::Delete), Delete),
+            (ctrl('u'), KillLineStart),
+            (ctrl('w'), KillWordLeft),
+            (ctrl('l'), ClearScreen),
+            (Key::code(KeyCode::Tab), Complete),
+            (Key::mcode(KeyCode::Tab, Modifiers::SHIFT), CompleteBack),
+            (ctrl('r'), SearchBackward),
+            (ctrl('s'), SearchForward),
+            (ctrl('v'), Paste),
+        ]);
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn chr(c: char) -> Key {
+    Key::code(KeyCode::Char(c))
+}
+
+fn shift(c: char) -> Key {
+    Key::mcode(KeyCode::Char(c), Modifiers::SHIFT)
+}
+
+fn ctrl(c: char) -> Key {
+    Key::mcode(KeyCode::Char(c), Modifiers::CONTROL)
+}
+
+fn alt(c: char) -> Key {
+    Key::mcode(KeyCode::Char(c), Modifiers::ALT)
+}
@@ -1,15 +1,26 @@
+mod completer;
+mod filter;
+mod highlighter;
+mod hinter;
+mod history;
+mod keymap;
 mod predicate;
 mod read_conf;
 mod term_read;
+mod undo;
+mod validator;
 mod vec2;
 
 use std::io::{self, Write};
 
 use crate::error::Result;
 
-pub(crate) use self::vec2::*;
+pub(crate) use self::{undo::*, vec2::*};
 
-pub use self::{predicate::*, read_conf::*, term_read::*};
+pub use self::{
+    completer::*, filter::*, highlighter::*, hinter::*, history::*, keymap::*,
+    predicate::*, read_conf::*, term_read::*, validator::*,
+};
 
 /// Read one line from standard input. This will use custom readline if
 /// supported. Otherwise it will fallback to the default readline function.
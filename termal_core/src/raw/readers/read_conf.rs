@@ -1,7 +1,11 @@
+use std::{borrow::Cow, fmt::Debug, sync::Arc};
+
 use crate::term_text::TermText;
 
+use super::{InputFilter, Validator};
+
 /// Configuration for terminal reader.
-#[derive(Debug, Clone, Default)]
+#[derive(Clone)]
 pub struct ReadConf<'a> {
     /// What thext should be edited. Empty by default.
     pub edit: Vec<char>,
@@ -9,4 +13,66 @@ pub struct ReadConf<'a> {
     pub edit_pos: Option<usize>,
     /// Prompt for the input. Empty by default.
     pub prompt: TermText<'a>,
+    /// Prompt shown on the start of each line after the first line in
+    /// multi-line mode. Empty by default.
+    pub continuation_prompt: TermText<'a>,
+    /// How the input is shown. Visible by default.
+    pub echo: Echo,
+    /// Filter of typed and pasted characters. `None` by default.
+    pub filter: Option<Arc<dyn InputFilter + Send + Sync>>,
+    /// Validator of the input when it is submitted. `None` by default.
+    pub validator: Option<Arc<dyn Validator + Send + Sync>>,
+    /// Message shown below the input when incomplete input is submitted in
+    /// single line mode. `"The input is incomplete."` by default.
+    pub incomplete_msg: Cow<'a, str>,
+    /// Line shown below the completion candidates that don't fit. `{}` is
+    /// replaced with the number of the candidates that are not shown.
+    /// `"... and {} more"` by default.
+    pub more_candidates_msg: Cow<'a, str>,
+}
+
+impl Default for ReadConf<'_> {
+    fn default() -> Self {
+        Self {
+            edit: vec![],
+            edit_pos: None,
+            prompt: TermText::default(),
+            continuation_prompt: TermText::default(),
+            echo: Echo::default(),
+            filter: None,
+            validator: None,
+            incomplete_msg: "The input is incomplete.".into(),
+            more_candidates_msg: "... and {} more".into(),
+        }
+    }
+}
+
+impl Debug for ReadConf<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadConf")
+            .field("edit", &self.edit)
+            .field("edit_pos", &self.edit_pos)
+            .field("prompt", &self.prompt)
+            .field("continuation_prompt", &self.continuation_prompt)
+            .field("echo", &self.echo)
+            .field("filter", &self.filter.is_some())
+            .field("validator", &self.validator.is_some())
+            .field("incomplete_msg", &self.incomplete_msg)
+            .field("more_candidates_msg", &self.more_candidates_msg)
+            .finish()
+    }
+}
+
+/// How the input of [`TermRead`] is shown.
+///
+/// [`TermRead`]: super::TermRead
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Echo {
+    /// The input is shown as typed.
+    #[default]
+    Visible,
+    /// Nothing is shown.
+    Hidden,
+    /// The given char is shown for each grapheme of the input.
+    Mask(char),
 }
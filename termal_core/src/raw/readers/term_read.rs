@@ -1,21 +1,41 @@
 use std::{
-    collections::VecDeque, io::Write, mem, ops::RangeBounds, time::Duration,
+    borrow::Cow,
+    collections::VecDeque,
+    io::Write,
+    mem,
+    ops::{Bound, Deref, DerefMut, Range, RangeBounds},
+    ptr,
+    sync::Arc,
+    sync::atomic::{self, Ordering},
+    time::Duration,
 };
 
+use unicode_segmentation::{
+    GraphemeCursor, GraphemeIncomplete, UnicodeSegmentation,
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
 use crate::{
     codes,
     error::{Error, Result},
     raw::{
         IoProvider, StdioProvider, Terminal,
         events::{Event, Key, KeyCode, Modifiers, Status},
-        term_size,
     },
     term_text::TermText,
 };
 
-use super::{Predicate, ReadConf, Vec2};
+use super::{
+    Candidate, Command, Completer, Echo, EditMode, Highlighter, Hinter,
+    History, InputFilter, Keymap, Predicate, ReadConf, UndoGroup, UndoStack,
+    Validation, Validator, Vec2,
+};
 
-/// Terminal reader. Supports only single line. Newlines are skipped.
+/// Terminal reader. Supports only single line unless multi-line mode is
+/// enabled with [`TermRead::set_multiline`]. Otherwise newlines are skipped.
+///
+/// The cursor moves by grapheme clusters and wide characters take two
+/// columns.
 ///
 /// ## Unstable API
 ///
@@ -24,8 +44,9 @@ pub struct TermRead<'t, 'p, P, T: IoProvider = StdioProvider>
 where
     P: Predicate<Event>,
 {
-    buf: Vec<char>,
+    buf: InputBuf,
     prompt: TermText<'p>,
+    continuation_prompt: TermText<'p>,
     pbuf: String,
     pos: usize,
     term: &'t mut Terminal<T>,
@@ -34,9 +55,104 @@ where
     // TODO: use bitflags
     // TODO: option to exit on ctrl+c
     finished: bool,
+    /// The input was ended by eof instead of being accepted.
+    eof: bool,
     paste: bool,
     last_event: Option<Event>,
     queue: VecDeque<Event>,
+    /// Index of the shown history entry. `None` if the edited line is shown.
+    hist_idx: Option<usize>,
+    /// The edited line saved while the history is shown.
+    hist_saved: (Vec<char>, usize),
+    /// Only history entries with this prefix are shown.
+    hist_prefix: String,
+    /// Incremental history search. `None` if not searching.
+    search: Option<Search<'p>>,
+    /// Range of characters in the buffer that is highlighted.
+    highlight: Option<Range<usize>>,
+    completer: Option<Box<dyn Completer>>,
+    /// Ongoing completion. `None` if not completing.
+    completion: Option<Completion>,
+    /// Number of lines with candidates or message shown below the input.
+    listing: usize,
+    hinter: Option<Box<dyn Hinter>>,
+    /// The hint that is shown after the input.
+    hint: String,
+    highlighter: Option<Box<dyn Highlighter>>,
+    /// The buffer as it was last printed with the highlighter.
+    highlighted: Vec<char>,
+    /// SGR sequences that are printed before the char at the index in
+    /// `highlighted`.
+    styles: Vec<String>,
+    multiline: bool,
+    validator: Option<Arc<dyn Validator>>,
+    filter: Option<Arc<dyn InputFilter>>,
+    keymap: Keymap,
+    mode: EditMode,
+    /// Operator that waits for motion in normal mode.
+    operator: Option<Command>,
+    /// Killed texts. The last is the newest.
+    kill_ring: VecDeque<String>,
+    /// Range of the last yanked text and index of it in the kill ring.
+    yank: Option<(Range<usize>, usize)>,
+    /// The last executed command.
+    last_cmd: Option<Command>,
+    undo: UndoStack,
+    echo: Echo,
+    incomplete_msg: Cow<'p, str>,
+    more_candidates_msg: Cow<'p, str>,
+}
+
+/// Maximum number of texts in the kill ring.
+const KILL_RING_LEN: usize = 16;
+/// Number of chars reserved in the buffer when the input is not visible.
+const HIDDEN_CAPACITY: usize = 256;
+
+/// The edited chars. They are zeroed on drop, so that hidden input doesn't
+/// stay in memory.
+#[derive(Default)]
+struct InputBuf(Vec<char>);
+
+impl Deref for InputBuf {
+    type Target = Vec<char>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for InputBuf {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Drop for InputBuf {
+    fn drop(&mut self) {
+        zero_chars(&mut self.0);
+    }
+}
+
+/// State of completion when cycling through candidates.
+struct Completion {
+    candidates: Vec<Candidate>,
+    /// Index of the applied candidate. `None` if the original is shown.
+    idx: Option<usize>,
+    /// The original buffer and position.
+    saved: (Vec<char>, usize),
+}
+
+/// State of incremental history search.
+struct Search<'p> {
+    query: String,
+    /// Index of the matched history entry.
+    idx: Option<usize>,
+    reverse: bool,
+    failed: bool,
+    /// The original prompt.
+    prompt: TermText<'p>,
+    /// The original buffer and position.
+    saved: (Vec<char>, usize),
 }
 
 impl<'t, T: IoProvider> TermRead<'t, '_, KeyCode, T> {
@@ -62,24 +178,54 @@ where
         exit: P,
         mut conf: ReadConf<'p>,
     ) -> Self {
-        let pos = conf
-            .edit_pos
-            .unwrap_or(conf.edit.len())
-            .min(conf.edit.len());
-        conf.edit.retain(|c| !c.is_ascii_control());
-        Self {
-            buf: conf.edit,
+        let size = get_size(term).unwrap_or((usize::MAX, usize::MAX).into());
+        if conf.echo != Echo::Visible {
+            conf.edit.reserve(HIDDEN_CAPACITY);
+        }
+        let mut res = Self {
+            buf: InputBuf(conf.edit),
             pbuf: String::new(),
-            pos,
+            pos: 0,
             term,
             exit,
             prompt: conf.prompt,
-            size: (usize::MAX, usize::MAX).into(),
+            continuation_prompt: conf.continuation_prompt,
+            size,
             finished: false,
+            eof: false,
             paste: false,
             last_event: None,
             queue: VecDeque::new(),
-        }
+            hist_idx: None,
+            hist_saved: (vec![], 0),
+            hist_prefix: String::new(),
+            search: None,
+            highlight: None,
+            completer: None,
+            completion: None,
+            listing: 0,
+            hinter: None,
+            hint: String::new(),
+            highlighter: None,
+            highlighted: vec![],
+            styles: vec![],
+            multiline: false,
+            validator: conf.validator.map(|v| v as Arc<dyn Validator>),
+            filter: conf.filter.map(|f| f as Arc<dyn InputFilter>),
+            keymap: Keymap::default(),
+            mode: EditMode::Insert,
+            operator: None,
+            kill_ring: VecDeque::new(),
+            yank: None,
+            last_cmd: None,
+            undo: UndoStack::default(),
+            echo: conf.echo,
+            incomplete_msg: conf.incomplete_msg,
+            more_candidates_msg: conf.more_candidates_msg,
+        };
+        res.filter_buf();
+        res.set_pos(conf.edit_pos);
+        res
     }
 
     /// Edit the given string.
@@ -89,6 +235,7 @@ where
         pos: Option<usize>,
     ) -> Result<()> {
         self.set_edit(&s, pos);
+        self.undo.clear();
         self.reshow()?;
         s.clear();
         self.finish_to_str(s)
@@ -101,6 +248,7 @@ where
         pos: Option<usize>,
     ) -> Result<String> {
         self.set_edit(s, pos);
+        self.undo.clear();
         self.reshow()?;
         self.finish()
     }
@@ -112,11 +260,12 @@ where
         s: &mut Vec<char>,
         pos: Option<usize>,
     ) -> Result<()> {
-        mem::swap(&mut self.buf, s);
+        mem::swap(&mut *self.buf, s);
         self.set_pos(pos);
+        self.undo.clear();
         self.reshow()?;
         self.get_all()?;
-        mem::swap(&mut self.buf, s);
+        mem::swap(&mut *self.buf, s);
         self.clear();
         Ok(())
     }
@@ -138,7 +287,10 @@ where
     /// Continue reading all data and reset.
     pub fn finish_to_str(&mut self, s: &mut String) -> Result<()> {
         self.get_all()?;
-        s.extend(&self.buf);
+        // Reserve first so that the string doesn't reallocate with part of
+        // the input.
+        s.reserve(self.buf.iter().map(|c| c.len_utf8()).sum());
+        s.extend(self.buf.iter());
         self.clear();
         Ok(())
     }
@@ -160,10 +312,20 @@ where
         self.pos
     }
 
-    /// Set string to edit.
+    /// Set string to edit. It is filtered in the same way as typed text. The
+    /// change may be undone.
     pub fn set_edit(&mut self, s: impl AsRef<str>, pos: Option<usize>) {
-        self.buf.clear();
-        self.buf.extend(s.as_ref().chars());
+        let old = mem::take(&mut *self.buf);
+        self.undo_change(old, self.pos, None);
+        let s = s.as_ref();
+        self.reserve(s.len().max(HIDDEN_CAPACITY));
+        insert_chars(
+            &mut self.buf,
+            0,
+            s.chars(),
+            self.filter.as_deref(),
+            self.multiline,
+        );
         self.set_pos(pos);
     }
 
@@ -174,14 +336,24 @@ where
 
     /// Reset the buffer.
     pub fn clear(&mut self) {
+        if self.echo != Echo::Visible {
+            self.zero_buf();
+        }
         self.pos = 0;
         self.buf.clear();
         self.finished = false;
+        self.eof = false;
+        self.hist_idx = None;
+        self.mode = EditMode::Insert;
+        self.operator = None;
+        self.last_cmd = None;
+        self.undo.clear();
     }
 
     /// Refresh the view.
     pub fn reshow(&mut self) -> Result<()> {
         self.reprint_all();
+        self.refresh_hint();
         self.commit()
     }
 
@@ -190,16 +362,78 @@ where
         self.prompt = prompt.into();
     }
 
+    /// Set the prompt shown on the start of each line after the first line in
+    /// multi-line mode.
+    pub fn set_continuation_prompt(
+        &mut self,
+        prompt: impl Into<TermText<'p>>,
+    ) {
+        self.continuation_prompt = prompt.into();
+    }
+
+    /// Set the message shown when incomplete input is submitted in single
+    /// line mode.
+    pub fn set_incomplete_msg(&mut self, msg: impl Into<Cow<'p, str>>) {
+        self.incomplete_msg = msg.into();
+    }
+
+    /// Set the line shown below completion candidates that don't fit. `{}` is
+    /// replaced with the number of the candidates that are not shown.
+    pub fn set_more_candidates_msg(&mut self, msg: impl Into<Cow<'p, str>>) {
+        self.more_candidates_msg = msg.into();
+    }
+
+    /// Set how the input is shown. If the input is not visible, it is not
+    /// added to history, history navigation, hints and highlighting are
+    /// disabled, killed text is not saved and the buffer is zeroed when it
+    /// is cleared, reallocated or when the reader is dropped. Completion is
+    /// also disabled.
+    pub fn set_echo(&mut self, echo: Echo) {
+        self.echo = echo;
+        self.reserve(HIDDEN_CAPACITY);
+    }
+
     /// Reconfigure the reader.
     pub fn configure(&mut self, conf: ReadConf<'p>) {
+        self.set_echo(conf.echo);
+        self.validator = conf.validator.map(|v| v as Arc<dyn Validator>);
+        self.filter = conf.filter.map(|f| f as Arc<dyn InputFilter>);
         self.set_buf(conf.edit, conf.edit_pos);
         self.set_prompt(conf.prompt);
+        self.set_continuation_prompt(conf.continuation_prompt);
+        self.incomplete_msg = conf.incomplete_msg;
+        self.more_candidates_msg = conf.more_candidates_msg;
+    }
+
+    /// Enable multi-line mode. Enter will insert new line unless the validator
+    /// says that the input is complete. Alt+Enter always inserts new line.
+    /// Up and down arrows move between the lines and home and end move to
+    /// the start and end of the current line.
+    pub fn set_multiline(&mut self, validator: impl Validator + 'static) {
+        self.multiline = true;
+        self.set_validator(validator);
     }
 
-    /// Set the read buffer. It is filtered for non control characters.
+    /// Set the validator of the input. It is called when the input is
+    /// submitted. If it returns [`Validation::Invalid`], the message is shown
+    /// below the input and the input is not submitted.
+    pub fn set_validator(&mut self, validator: impl Validator + 'static) {
+        self.validator = Some(Arc::new(validator));
+    }
+
+    /// Set the filter of characters inserted to the buffer.
+    pub fn set_filter(&mut self, filter: impl InputFilter + 'static) {
+        self.filter = Some(Arc::new(filter));
+    }
+
+    /// Set the read buffer. It is filtered for non control characters (new
+    /// lines are kept in multi-line mode) and with the input filter. The
+    /// change may be undone.
     pub fn set_buf(&mut self, buf: Vec<char>, pos: Option<usize>) {
-        self.buf = buf;
-        self.buf.retain(|c| !c.is_ascii_control());
+        let old = mem::replace(&mut *self.buf, buf);
+        self.undo_change(old, self.pos, None);
+        self.filter_buf();
+        self.reserve(HIDDEN_CAPACITY);
         self.set_pos(pos);
     }
 
@@ -216,6 +450,9 @@ where
         }
 
         self.finished = self.read_one_inner()?;
+        if self.finished {
+            self.accept()?;
+        }
         Ok(self.finished)
     }
 
@@ -229,14 +466,35 @@ where
         self.exit = c;
     }
 
-    /// Modify the buffer. Control characters are ignored.
+    /// Modify the buffer. Control characters are ignored (new lines are kept
+    /// in multi-line mode) and the inserted chars are filtered in the same
+    /// way as typed text. The change may be undone.
     pub fn splice(
         &mut self,
         range: impl RangeBounds<usize>,
         it: impl IntoIterator<Item = char>,
     ) {
-        self.buf
-            .splice(range, it.into_iter().filter(|c| !c.is_ascii_control()));
+        if self.echo == Echo::Visible {
+            self.undo_change(self.buf.clone(), self.pos, None);
+        }
+        let start = match range.start_bound() {
+            Bound::Included(s) => *s,
+            Bound::Excluded(s) => s.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        self.buf.drain(range);
+        let mut it: Vec<_> = it.into_iter().collect();
+        self.reserve(it.len());
+        insert_chars(
+            &mut self.buf,
+            start,
+            it.iter().copied(),
+            self.filter.as_deref(),
+            self.multiline,
+        );
+        if self.echo != Echo::Visible {
+            zero_chars(&mut it);
+        }
         self.pos = self.pos.min(self.buf.len());
     }
 
@@ -245,6 +503,50 @@ where
         self.last_event.as_ref()
     }
 
+    /// Set the completer used when tab is pressed.
+    ///
+    /// If there are multiple candidates, their common prefix is inserted. If
+    /// there is no common prefix, the candidates are listed below the input.
+    /// Pressing tab again cycles through the candidates (shift+tab cycles
+    /// backwards).
+    pub fn set_completer(&mut self, completer: impl Completer + 'static) {
+        self.completer = Some(Box::new(completer));
+    }
+
+    /// Set the hinter that provides hints shown after the cursor. The hint may
+    /// be accepted with the right arrow or end.
+    pub fn set_hinter(&mut self, hinter: impl Hinter + 'static) {
+        self.hinter = Some(Box::new(hinter));
+    }
+
+    /// Set the highlighter that styles the input when it is printed.
+    pub fn set_highlighter(
+        &mut self,
+        highlighter: impl Highlighter + 'static,
+    ) {
+        self.highlighter = Some(Box::new(highlighter));
+        self.highlighted.clear();
+        self.styles.clear();
+    }
+
+    /// Set the keymap that maps keys to editor commands. Emacs like keymap
+    /// ([`Keymap::emacs`]) is used by default.
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+        self.mode = EditMode::Insert;
+        self.operator = None;
+    }
+
+    /// Get the keymap. It may be used to rebind keys.
+    pub fn keymap_mut(&mut self) -> &mut Keymap {
+        &mut self.keymap
+    }
+
+    /// Get the current mode of the editor.
+    pub fn edit_mode(&self) -> EditMode {
+        self.mode
+    }
+
     /// Queue event to the reader.
     pub fn queue(&mut self, evt: impl IntoIterator<Item = Event>) {
         self.queue.extend(evt);
@@ -274,16 +576,32 @@ where
 
         while !self.read_one_inner()? {}
         self.finished = true;
+        self.accept()
+    }
+
+    /// Add the input to history. Input ended by eof is not added.
+    fn accept(&mut self) -> Result<()> {
+        if self.search.is_some() {
+            self.end_search(true);
+        }
+        self.completion = None;
+        self.clear_listing();
+        self.clear_hint();
+        self.commit()?;
+        self.hist_idx = None;
+        if self.buf.is_empty() || self.echo != Echo::Visible || self.eof {
+            return Ok(());
+        }
+        if let Some(hist) = self.term.history_mut() {
+            hist.add(&self.buf.iter().collect::<String>())?;
+        }
         Ok(())
     }
 
     fn resize(&mut self) {
-        let Ok(size) =
-            term_size().map(|s| Vec2::new(s.char_width, s.char_height))
-        else {
+        let Some(size) = get_size(self.term) else {
             return;
         };
-        self.size.map(|a| if a == 0 { usize::MAX } else { a });
         if self.size == size {
             return;
         }
@@ -305,29 +623,129 @@ where
     }
 
     fn read_next(&mut self) -> Result<bool> {
-        if let Some(evt) = self.queue.pop_front() {
-            return self.handle_event(evt);
+        let evt = match self.queue.pop_front() {
+            Some(e) => e,
+            None => match self.term.read() {
+                Ok(e) => e,
+                Err(Error::StdInEof) => {
+                    self.eof = true;
+                    self.end();
+                    self.commit()?;
+                    return Ok(true);
+                }
+                Err(e) => Err(e)?,
+            },
+        };
+
+        if self.handle_event(evt)? {
+            return Ok(true);
         }
+        self.refresh_highlight();
+        self.refresh_hint();
+        self.commit()?;
+        Ok(false)
+    }
 
-        let evt = match self.term.read() {
-            Ok(e) => e,
-            Err(Error::StdInEof) => {
-                self.end();
-                self.commit()?;
-                return Ok(true);
+    /// Handle the event and record the change for undo.
+    fn handle_event(&mut self, evt: Event) -> Result<bool> {
+        let cmd = match &evt {
+            Event::KeyPress(k) if self.search.is_none() => {
+                self.keymap.get(k, self.mode)
             }
-            Err(e) => Err(e)?,
+            _ => None,
         };
+        if matches!(cmd, Some(Command::Undo | Command::Redo)) {
+            return self.handle_event_inner(evt);
+        }
 
-        self.handle_event(evt)
+        let group = match (&evt, cmd) {
+            (Event::KeyPress(_), _) if self.search.is_some() => {
+                Some(UndoGroup::Search)
+            }
+            (_, Some(Command::Complete | Command::CompleteBack)) => {
+                Some(UndoGroup::Completion)
+            }
+            (
+                _,
+                Some(Command::Yank | Command::YankAfter | Command::YankPop),
+            ) => Some(UndoGroup::Yank),
+            (_, Some(Command::MoveUp | Command::MoveDown)) => {
+                Some(UndoGroup::History)
+            }
+            (_, Some(Command::SearchBackward | Command::SearchForward)) => {
+                Some(UndoGroup::Search)
+            }
+            (Event::KeyPress(k), None) if k.key_char.is_some() => {
+                Some(UndoGroup::Typing)
+            }
+            _ => None,
+        };
+
+        if self.echo != Echo::Visible {
+            // Don't make copies of input that is not visible.
+            return self.handle_event_inner(evt);
+        }
+
+        let saved = (self.buf.clone(), self.pos);
+        let res = self.handle_event_inner(evt)?;
+        if saved.0 != *self.buf {
+            self.undo_change(saved.0, saved.1, group);
+        } else if group.is_none() {
+            self.undo.break_group();
+        }
+        Ok(res)
     }
 
-    fn handle_event(&mut self, evt: Event) -> Result<bool> {
+    fn handle_event_inner(&mut self, evt: Event) -> Result<bool> {
+        let key = match &evt {
+            Event::KeyPress(key) => Some(key),
+            _ => None,
+        };
+
+        if self.search.is_some()
+            && key.is_some_and(|k| self.handle_search_key(k))
+        {
+            self.last_event = Some(evt);
+            self.commit()?;
+            return Ok(false);
+        }
+
+        if self.multiline
+            && key.is_some_and(|k| {
+                k.code == KeyCode::Enter
+                    && k.modifiers.contains(Modifiers::ALT)
+            })
+        {
+            self.last_event = Some(evt);
+            self.insert("\n");
+            self.commit()?;
+            return Ok(false);
+        }
+
         if self.exit.matches(&evt) {
             self.last_event = Some(evt);
-            self.end();
+            match self.validator.as_ref().map(|v| v.validate(&self.buf)) {
+                Some(Validation::Incomplete) if self.multiline => {
+                    self.insert("\n");
+                }
+                Some(Validation::Incomplete) => {
+                    self.clear_listing();
+                    let msg = mem::take(&mut self.incomplete_msg);
+                    self.show_message(&msg);
+                    self.incomplete_msg = msg;
+                }
+                Some(Validation::Invalid(msg)) => {
+                    self.clear_listing();
+                    self.show_message(&msg);
+                }
+                _ => {
+                    self.end();
+                    self.commit()?;
+                    return Ok(true);
+                }
+            }
             self.commit()?;
-            return Ok(true);
+            return Ok(false);
         }
 
         match evt {
@@ -345,6 +763,13 @@ where
                 }
                 Ok(false)
             }
+            Event::Paste(mut s) => {
+                let ml = self.multiline;
+                s.retain(|c| is_allowed(c, ml));
+                self.insert(&s);
+                self.commit()?;
+                Ok(false)
+            }
             _ => {
                 self.last_event = Some(evt);
                 Ok(false)
@@ -353,110 +778,1051 @@ where
     }
 
     fn handle_key_press(&mut self, key: Key) -> Result<bool> {
-        if let Some(chr) = key.key_char {
-            self.buf.insert(self.pos, chr);
+        let cmd = self.keymap.get(&key, self.mode);
+        if !matches!(cmd, Some(Command::MoveUp | Command::MoveDown)) {
+            self.hist_idx = None;
+        }
+        if !matches!(cmd, Some(Command::Complete | Command::CompleteBack)) {
+            self.completion = None;
+            self.clear_listing();
+        }
+
+        match (self.operator.take(), cmd) {
+            (Some(op), motion) => self.operate(op, motion),
+            (None, Some(cmd)) => self.execute(cmd),
+            (None, None) => self.insert_key(&key),
+        }
+        self.last_cmd = cmd;
+
+        self.commit()?;
+        Ok(false)
+    }
+
+    /// Insert the char of the key if it has one.
+    fn insert_key(&mut self, key: &Key) {
+        let Some(chr) = key.key_char else {
+            return;
+        };
+        if self.mode != EditMode::Insert
+            || !is_allowed(chr, self.multiline)
+            || self
+                .filter
+                .as_ref()
+                .is_some_and(|f| !f.accept(&self.buf, self.pos, chr))
+        {
+            return;
+        }
+
+        self.reserve(1);
+        self.buf.insert(self.pos, chr);
+        if self.pos + 1 < self.buf.len() {
+            self.reprint_pos();
+            self.move_to_pos(self.pos + 1);
+        } else {
+            self.print_from_dont_move(self.pos);
+            self.pos += 1;
+        }
+    }
 
-            if self.pos + 1 < self.buf.len() {
-                self.reprint_pos();
+    /// Execute editor command.
+    fn execute(&mut self, cmd: Command) {
+        match cmd {
+            Command::MoveLeft => self.move_left(),
+            Command::MoveRight | Command::MoveLineEnd
+                if self.pos == self.buf.len() && !self.hint.is_empty() =>
+            {
+                // The hint is overwritten by the inserted text.
+                let hint = mem::take(&mut self.hint);
+                self.insert(&hint);
+            }
+            Command::MoveRight => self.move_right(),
+            Command::MoveWordLeft => self.move_to_pos(self.word_left()),
+            Command::MoveWordRight => self.move_to_pos(self.word_right()),
+            Command::MoveLineStart => {
+                self.move_to_pos(self.line_start(self.pos))
+            }
+            Command::MoveLineEnd => self.move_to_pos(self.line_end(self.pos)),
+            Command::MoveUp if self.line_start(self.pos) != 0 => {
+                self.line_up()
+            }
+            Command::MoveUp => self.history_prev(),
+            Command::MoveDown if self.line_end(self.pos) != self.buf.len() => {
+                self.line_down()
+            }
+            Command::MoveDown => self.history_next(),
+            Command::Backspace => self.backspace(),
+            Command::Delete => self.delete(),
+            Command::KillLineEnd => {
+                self.kill(self.pos..self.line_end(self.pos))
+            }
+            Command::KillLineStart => {
+                self.kill(self.line_start(self.pos)..self.pos)
+            }
+            Command::KillWordLeft => self.kill(self.word_left()..self.pos),
+            Command::KillWordRight => self.kill(self.pos..self.word_right()),
+            Command::Yank => self.yank(),
+            Command::YankAfter => {
+                if self.pos < self.buf.len() {
+                    self.move_right();
+                }
+                self.yank();
+            }
+            Command::YankPop => self.yank_pop(),
+            Command::TransposeChars => self.transpose(),
+            Command::Undo => self.undo(),
+            Command::Redo => self.redo(),
+            Command::ClearScreen => self.clear_screen(),
+            Command::Complete => self.complete(false),
+            Command::CompleteBack => self.complete(true),
+            Command::SearchBackward => self.search_step(true),
+            Command::SearchForward => self.search_step(false),
+            Command::Paste => {
+                self.paste = true;
+                self.pbuf += codes::REQUEST_SELECTION;
+            }
+            Command::NormalMode => {
+                if self.keymap.has_normal_mode() {
+                    self.mode = EditMode::Normal;
+                    self.move_left();
+                }
+            }
+            Command::InsertMode => self.mode = EditMode::Insert,
+            Command::InsertAfter => {
+                self.mode = EditMode::Insert;
                 self.move_right();
-            } else {
-                self.print_from_dont_move(self.pos);
-                self.pos += 1;
-                if self.cur_pos().x == 0 {
-                    self.pbuf += "\r\n";
+            }
+            Command::InsertLineStart => {
+                self.mode = EditMode::Insert;
+                self.move_to_pos(self.line_start(self.pos));
+            }
+            Command::InsertLineEnd => {
+                self.mode = EditMode::Insert;
+                self.move_to_pos(self.line_end(self.pos));
+            }
+            Command::DeleteMotion | Command::ChangeMotion => {
+                self.operator = Some(cmd);
+            }
+        }
+    }
+
+    /// Kill the text over which the motion moves and switch to insert mode
+    /// if the operator is [`Command::ChangeMotion`]. Other commands than
+    /// motions cancel the operator.
+    fn operate(&mut self, op: Command, motion: Option<Command>) {
+        let change = op == Command::ChangeMotion;
+        let pos = self.pos;
+        let range = match motion {
+            Some(m) if m == op => {
+                let start = self.line_start(pos);
+                let end = self.line_end(pos);
+                // Delete also the newline of the line.
+                if change {
+                    start..end
+                } else if end < self.buf.len() {
+                    start..end + 1
+                } else {
+                    start.saturating_sub(1)..end
                 }
             }
+            Some(Command::MoveLeft) => self.prev_grapheme(pos)..pos,
+            Some(Command::MoveRight) => pos..self.next_grapheme(pos),
+            Some(Command::MoveWordLeft) => self.word_left()..pos,
+            Some(Command::MoveWordRight) if change => pos..self.word_right(),
+            Some(Command::MoveWordRight) => pos..self.word_start_right(),
+            Some(Command::MoveLineStart) => self.line_start(pos)..pos,
+            Some(Command::MoveLineEnd) => pos..self.line_end(pos),
+            _ => return,
+        };
+        self.kill(range);
+        if change {
+            self.mode = EditMode::Insert;
+        }
+    }
 
-            self.commit()?;
-            return Ok(false);
+    /// Remove the range from the buffer and add it to the kill ring.
+    /// Consecutive kills are joined.
+    fn kill(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        if self.echo != Echo::Visible {
+            self.move_to_pos(range.start);
+            self.buf.drain(range);
+            self.reprint_pos();
+            return;
         }
 
-        match key.code {
-            KeyCode::Left => {
-                if key.modifiers.contains(Modifiers::CONTROL) {
-                    self.move_word_left();
-                } else {
-                    self.move_left()
+        let text: String = self.buf[range.clone()].iter().collect();
+        let joined = matches!(
+            self.last_cmd,
+            Some(
+                Command::KillLineEnd
+                    | Command::KillLineStart
+                    | Command::KillWordLeft
+                    | Command::KillWordRight
+            )
+        );
+        match self.kill_ring.back_mut() {
+            Some(last) if joined && range.start < self.pos => {
+                last.insert_str(0, &text)
+            }
+            Some(last) if joined => *last += &text,
+            _ => {
+                self.kill_ring.push_back(text);
+                if self.kill_ring.len() > KILL_RING_LEN {
+                    self.kill_ring.pop_front();
                 }
             }
-            KeyCode::Right => {
-                if key.modifiers.contains(Modifiers::CONTROL) {
-                    self.move_word_right();
+        }
+
+        self.move_to_pos(range.start);
+        self.buf.drain(range);
+        self.reprint_pos();
+    }
+
+    /// Insert the last killed text.
+    fn yank(&mut self) {
+        let Some(idx) = self.kill_ring.len().checked_sub(1) else {
+            return;
+        };
+        let start = self.pos;
+        let text = self.kill_ring[idx].clone();
+        self.insert(&text);
+        self.yank = Some((start..self.pos, idx));
+    }
+
+    /// Replace the yanked text with the previous killed text.
+    fn yank_pop(&mut self) {
+        if !matches!(
+            self.last_cmd,
+            Some(Command::Yank | Command::YankAfter | Command::YankPop)
+        ) {
+            return;
+        }
+        let Some((range, idx)) = self.yank.take() else {
+            return;
+        };
+
+        let idx = idx.checked_sub(1).unwrap_or(self.kill_ring.len() - 1);
+        let text = self.kill_ring[idx].clone();
+        let start = range.start;
+        self.move_to_pos(start);
+        self.reserve(text.len());
+        self.buf.drain(range);
+        let end = insert_chars(
+            &mut self.buf,
+            start,
+            text.chars(),
+            self.filter.as_deref(),
+            self.multiline,
+        );
+        self.reprint_from_move_to(start, end);
+        self.yank = Some((start..self.pos, idx));
+    }
+
+    /// Swap the grapheme before the cursor with the grapheme at the cursor.
+    /// At the end of the line, the last two graphemes are swapped.
+    fn transpose(&mut self) {
+        let line_start = self.line_start(self.pos);
+        if self.pos == 0 || self.pos == line_start {
+            return;
+        }
+        let pos = if self.pos == self.line_end(self.pos) {
+            self.prev_grapheme(self.pos)
+        } else {
+            self.pos
+        };
+        if pos <= line_start {
+            return;
+        }
+
+        let start = self.prev_grapheme(pos);
+        let end = self.next_grapheme(pos);
+        self.move_to_pos(start);
+        self.buf[start..end].rotate_left(pos - start);
+        self.reprint_from_move_to(start, end);
+    }
+
+    /// Record change for undo. Changes of input that is not visible are not
+    /// recorded and the old buffer is zeroed.
+    fn undo_change(
+        &mut self,
+        mut buf: Vec<char>,
+        pos: usize,
+        group: Option<UndoGroup>,
+    ) {
+        if self.echo == Echo::Visible {
+            self.undo.change(buf, pos, group);
+        } else {
+            zero_chars(&mut buf);
+        }
+    }
+
+    /// Make sure that `additional` chars may be inserted to the buffer
+    /// without reallocation. If the input is not visible, the old allocation
+    /// is zeroed.
+    fn reserve(&mut self, additional: usize) {
+        if self.echo == Echo::Visible
+            || self.buf.capacity() - self.buf.len() >= additional
+        {
+            return;
+        }
+        let cap = (self.buf.len() + additional)
+            .max(self.buf.capacity() * 2)
+            .max(HIDDEN_CAPACITY);
+        let mut buf = Vec::with_capacity(cap);
+        buf.extend_from_slice(&self.buf);
+        zero_chars(&mut mem::replace(&mut *self.buf, buf));
+    }
+
+    /// Replace the buffer. If the input is not visible, the old buffer is
+    /// zeroed.
+    fn replace_buf(&mut self, buf: Vec<char>) {
+        let mut old = mem::replace(&mut *self.buf, buf);
+        if self.echo != Echo::Visible {
+            zero_chars(&mut old);
+        }
+    }
+
+    /// Overwrite the buffer with zeros so that the input doesn't stay in
+    /// memory.
+    fn zero_buf(&mut self) {
+        zero_chars(&mut self.buf);
+    }
+
+    /// Remove chars that are not allowed or that are rejected by the filter
+    /// from the buffer.
+    fn filter_buf(&mut self) {
+        let ml = self.multiline;
+        self.buf.retain(|c| is_allowed(*c, ml));
+        let Some(filter) = self.filter.as_deref() else {
+            return;
+        };
+        let cap = self.buf.capacity();
+        let mut old = mem::replace(&mut *self.buf, Vec::with_capacity(cap));
+        insert_chars(&mut self.buf, 0, old.iter().copied(), Some(filter), ml);
+        if self.echo != Echo::Visible {
+            zero_chars(&mut old);
+        }
+    }
+
+    /// Restore the state before the last change.
+    fn undo(&mut self) {
+        if self.echo != Echo::Visible {
+            return;
+        }
+        if let Some((buf, pos)) = self.undo.undo(self.buf.clone(), self.pos) {
+            self.show_changed(buf, pos);
+        }
+    }
+
+    /// Restore the state before the last undo.
+    fn redo(&mut self) {
+        if self.echo != Echo::Visible {
+            return;
+        }
+        if let Some((buf, pos)) = self.undo.redo(self.buf.clone(), self.pos) {
+            self.show_changed(buf, pos);
+        }
+    }
+
+    /// Clear the screen and show the input at the top of the screen.
+    fn clear_screen(&mut self) {
+        let pos = self.pos;
+        self.hint.clear();
+        self.listing = 0;
+        self.pbuf += codes::ERASE_SCREEN;
+        self.pbuf += codes::MOVE_HOME;
+        self.reprint_with_prompt_dont_move();
+        self.move_to_pos(pos);
+    }
+
+    fn insert(&mut self, s: &str) {
+        self.hist_idx = None;
+        self.completion = None;
+        self.clear_listing();
+        self.reserve(s.len());
+        let end = insert_chars(
+            &mut self.buf,
+            self.pos,
+            s.chars(),
+            self.filter.as_deref(),
+            self.multiline,
+        );
+        self.reprint_from_move_to(self.pos, end);
+    }
+
+    /// Complete the input or cycle through the candidates.
+    fn complete(&mut self, back: bool) {
+        if self.echo != Echo::Visible {
+            return;
+        }
+        if let Some(c) = &mut self.completion {
+            let n = c.candidates.len();
+            c.idx = match (c.idx, back) {
+                (None, false) => Some(0),
+                (None, true) => n.checked_sub(1),
+                (Some(i), false) => Some(i + 1).filter(|i| *i < n),
+                (Some(i), true) => i.checked_sub(1),
+            };
+            let (mut buf, pos) = c.saved.clone();
+            let pos = match c.idx {
+                Some(i) => apply_candidate(
+                    &mut buf,
+                    &c.candidates[i],
+                    self.filter.as_deref(),
+                    self.multiline,
+                ),
+                None => pos,
+            };
+            self.show_changed(buf, pos);
+            self.show_listing();
+            return;
+        }
+
+        let Some(completer) = &mut self.completer else {
+            return;
+        };
+        let mut candidates = completer.complete(&self.buf, self.pos);
+        candidates.retain(|c| {
+            c.range.start <= c.range.end && c.range.end <= self.buf.len()
+        });
+
+        let cand = match &candidates[..] {
+            [] => return,
+            [c] => Some(c.clone()),
+            [c, rest @ ..] if rest.iter().all(|r| r.range == c.range) => {
+                let prefix = rest.iter().fold(&c.replacement[..], |p, r| {
+                    let len = p
+                        .char_indices()
+                        .zip(r.replacement.chars())
+                        .find(|((_, a), b)| a != b)
+                        .map_or(p.len().min(r.replacement.len()), |(i, _)| {
+                            i.0
+                        });
+                    &p[..len]
+                });
+                (prefix.chars().count() > c.range.len())
+                    .then(|| Candidate::new(c.range.clone(), prefix))
+            }
+            _ => None,
+        };
+
+        if let Some(cand) = cand {
+            let mut buf = self.buf.clone();
+            let pos = apply_candidate(
+                &mut buf,
+                &cand,
+                self.filter.as_deref(),
+                self.multiline,
+            );
+            self.show_changed(buf, pos);
+            return;
+        }
+
+        self.completion = Some(Completion {
+            candidates,
+            idx: None,
+            saved: (self.buf.clone(), self.pos),
+        });
+        self.show_listing();
+    }
+
+    /// Show the candidates of the completion below the input in columns.
+    fn show_listing(&mut self) {
+        let Some(c) = &self.completion else {
+            return;
+        };
+
+        let width = if self.size.x == usize::MAX {
+            80
+        } else {
+            self.size.x
+        };
+        let col_width = c
+            .candidates
+            .iter()
+            .map(|c| c.display().width() + 2)
+            .max()
+            .unwrap_or_default()
+            .min(width);
+        let cols = (width / col_width.max(1)).max(1);
+        let mut rows = c.candidates.len().div_ceil(cols);
+        // Keep the input on the screen.
+        let end = self.pos_of(self.buf.len());
+        let max_rows = if self.size.y == usize::MAX {
+            usize::MAX
+        } else {
+            self.size.y.saturating_sub(end.y + 2).max(1)
+        };
+        if rows > max_rows {
+            rows = max_rows.saturating_sub(1).max(1);
+        }
+        let shown = c.candidates.len().min(rows * cols);
+        let more = c.candidates.len() - shown;
+
+        let mut listing = String::new();
+        for r in 0..rows {
+            listing += "\r\n";
+            for col in 0..cols {
+                let i = col * rows + r;
+                if i >= shown {
+                    break;
+                }
+                let text: String = c.candidates[i]
+                    .display()
+                    .chars()
+                    .map(get_printable)
+                    .take(width.saturating_sub(1))
+                    .collect();
+                let pad = col_width.saturating_sub(text.width());
+                if c.idx == Some(i) {
+                    listing += codes::INVERSE;
+                    listing += &text;
+                    listing += codes::RESET_INVERSE;
                 } else {
-                    self.move_right()
+                    listing += &text;
+                }
+                if col + 1 < cols {
+                    listing.extend(std::iter::repeat_n(' ', pad));
                 }
             }
-            KeyCode::Backspace => self.backspace(),
-            KeyCode::Delete => self.delete(),
-            KeyCode::Home => self.home(),
-            KeyCode::End => self.end(),
-            KeyCode::Char('v') => {
-                if key.modifiers.contains(Modifiers::CONTROL) {
-                    self.paste = true;
-                    self.pbuf += codes::REQUEST_SELECTION;
+        }
+        if more != 0 {
+            listing += "\r\n";
+            listing +=
+                &self.more_candidates_msg.replace("{}", &more.to_string());
+            rows += 1;
+        }
+
+        self.show_below(&listing, rows);
+    }
+
+    /// Show error message below the input.
+    fn show_message(&mut self, msg: &str) {
+        let width = if self.size.x == usize::MAX {
+            usize::MAX
+        } else {
+            self.size.x
+        };
+
+        let mut text = String::new();
+        let mut rows = 0;
+        for line in msg.lines() {
+            text += "\r\n";
+            text += codes::RED_FG;
+            text.extend(
+                line.chars()
+                    .map(get_printable)
+                    .take(width.saturating_sub(1)),
+            );
+            text += codes::RESET_FG;
+            rows += 1;
+        }
+        self.show_below(&text, rows);
+    }
+
+    /// Show the text below the input. The text must start with new line and
+    /// have the given number of lines. It is erased with
+    /// [`Self::clear_listing`].
+    fn show_below(&mut self, text: &str, rows: usize) {
+        let pos = self.pos;
+        self.move_to_pos(self.buf.len());
+        let x = self.cur_pos().x;
+        self.pbuf += codes::ERASE_TO_END;
+        self.pbuf += text;
+        self.pbuf.push('\r');
+        self.move_rd_dif((x, 0).into(), (0, rows).into());
+        self.listing = rows;
+        self.move_to_pos(pos);
+    }
+
+    /// Erase the listing of candidates below the input.
+    fn clear_listing(&mut self) {
+        if self.listing == 0 {
+            return;
+        }
+        self.listing = 0;
+        let pos = self.pos;
+        self.move_to_pos(self.buf.len());
+        let x = self.cur_pos().x;
+        self.pbuf += codes::move_down!(1);
+        self.pbuf.push('\r');
+        self.pbuf += codes::ERASE_TO_END;
+        self.move_rd_dif((x, 0).into(), (0, 1).into());
+        self.move_to_pos(pos);
+    }
+
+    /// Reprint the whole input if it has changed, because the change may
+    /// affect highlighting of the rest of the input.
+    fn refresh_highlight(&mut self) {
+        if self.highlighter.is_some()
+            && self.echo == Echo::Visible
+            && self.highlighted != *self.buf
+        {
+            self.reprint_from(0);
+        }
+    }
+
+    /// Get new hint and show it after the input.
+    fn refresh_hint(&mut self) {
+        let new = match &mut self.hinter {
+            Some(h)
+                if self.pos == self.buf.len()
+                    && self.echo == Echo::Visible
+                    && self.search.is_none()
+                    && self.listing == 0 =>
+            {
+                h.hint(&self.buf, self.pos).unwrap_or_default()
+            }
+            _ => String::new(),
+        };
+
+        // The hint is always reprinted, because it may have been partially
+        // overwritten.
+        self.clear_hint();
+        self.hint = new.chars().filter(|c| !c.is_control()).collect();
+        if self.hint.is_empty() {
+            return;
+        }
+
+        let pos = self.pos;
+        self.move_to_pos(self.buf.len());
+        let end = self.cur_pos();
+        self.pbuf += codes::FAINT;
+        self.pbuf += &self.hint;
+        self.pbuf += codes::RESET_BOLD;
+        let hint_end = self
+            .hint
+            .graphemes(true)
+            .fold(end, |p, g| self.advance(p, g));
+        if hint_end.x == 0 {
+            self.pbuf += "\r\n";
+        }
+        self.move_rd_dif(end, hint_end);
+        self.move_to_pos(pos);
+    }
+
+    /// Erase the hint shown after the input.
+    fn clear_hint(&mut self) {
+        if mem::take(&mut self.hint).is_empty() {
+            return;
+        }
+        let pos = self.pos;
+        self.move_to_pos(self.buf.len());
+        self.pbuf += codes::ERASE_TO_END;
+        self.move_to_pos(pos);
+    }
+
+    /// Show the previous history entry that starts with the prefix.
+    fn history_prev(&mut self) {
+        if self.echo != Echo::Visible {
+            return;
+        }
+
+        let Some(hist) = self.term.history() else {
+            return;
+        };
+
+        if self.hist_idx.is_none() {
+            self.hist_prefix = self.buf[..self.pos].iter().collect();
+        }
+        let ml = self.multiline;
+        let cur: String = self.buf.iter().collect();
+        let end = self.hist_idx.unwrap_or(hist.len());
+        let Some((idx, entry)) = (0..end).rev().find_map(|i| {
+            hist.get(i)
+                .filter(|e| e.starts_with(&self.hist_prefix) && *e != cur)
+                .map(|e| (i, e.chars().filter(|c| is_allowed(*c, ml))))
+        }) else {
+            return;
+        };
+        let entry = entry.collect();
+
+        if self.hist_idx.is_none() {
+            self.hist_saved = (self.buf.clone(), self.pos);
+        }
+        self.hist_idx = Some(idx);
+        self.show_buf(entry, None);
+    }
+
+    /// Show the next history entry that starts with the prefix or the edited
+    /// line if there is no such entry.
+    fn history_next(&mut self) {
+        if self.echo != Echo::Visible {
+            return;
+        }
+
+        let (Some(idx), Some(hist)) = (self.hist_idx, self.term.history())
+        else {
+            return;
+        };
+
+        let cur: String = self.buf.iter().collect();
+        let ml = self.multiline;
+        let next = (idx + 1..hist.len()).find_map(|i| {
+            hist.get(i)
+                .filter(|e| e.starts_with(&self.hist_prefix) && *e != cur)
+                .map(|e| (i, e.chars().filter(|c| is_allowed(*c, ml))))
+        });
+
+        if let Some((idx, entry)) = next {
+            let entry = entry.collect();
+            self.hist_idx = Some(idx);
+            self.show_buf(entry, None);
+        } else {
+            self.hist_idx = None;
+            let (buf, pos) = mem::take(&mut self.hist_saved);
+            self.show_buf(buf, Some(pos));
+        }
+    }
+
+    /// Handle key while searching. Returns `false` if the key doesn't belong
+    /// to the search. In such case the search is accepted.
+    fn handle_search_key(&mut self, key: &Key) -> bool {
+        let ctrl = key.modifiers.contains(Modifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('r') if ctrl => self.search_step(true),
+            KeyCode::Char('s') if ctrl => self.search_step(false),
+            KeyCode::Char('g') if ctrl => self.end_search(false),
+            KeyCode::Esc => self.end_search(false),
+            KeyCode::Backspace => {
+                let search = self.search.as_mut().unwrap();
+                search.query.pop();
+                search.idx = None;
+                self.search_update();
+            }
+            _ => match key.key_char {
+                Some(c) if !c.is_control() => {
+                    self.search.as_mut().unwrap().query.push(c);
+                    self.search_update();
                 }
+                _ => {
+                    self.end_search(true);
+                    return false;
+                }
+            },
+        }
+        true
+    }
+
+    /// Start the search or move to the next match in the given direction.
+    fn search_step(&mut self, reverse: bool) {
+        if self.echo != Echo::Visible {
+            return;
+        }
+
+        let Some(hist) = self.term.history() else {
+            return;
+        };
+
+        let Some(search) = &mut self.search else {
+            self.search = Some(Search {
+                query: String::new(),
+                idx: None,
+                reverse,
+                failed: false,
+                prompt: self.prompt.clone(),
+                saved: (self.buf.clone(), self.pos),
+            });
+            self.show_search();
+            return;
+        };
+
+        search.reverse = reverse;
+        if search.query.is_empty() {
+            self.show_search();
+            return;
+        }
+
+        let from = match (search.idx, reverse) {
+            (Some(i), true) => i.checked_sub(1),
+            (Some(i), false) => Some(i + 1),
+            (None, true) => hist.len().checked_sub(1),
+            (None, false) => None,
+        };
+        match from
+            .and_then(|f| find_in_history(hist, &search.query, f, reverse))
+        {
+            Some(i) => {
+                search.idx = Some(i);
+                search.failed = false;
             }
-            _ => {}
+            None => search.failed = true,
         }
+        self.show_search();
+    }
 
-        self.commit()?;
+    /// Find match for the changed query. The current match is also checked.
+    fn search_update(&mut self) {
+        let (Some(hist), Some(search)) =
+            (self.term.history(), &mut self.search)
+        else {
+            return;
+        };
 
-        Ok(false)
+        let from = match search.idx {
+            Some(i) => Some(i),
+            None if search.reverse => hist.len().checked_sub(1),
+            None => None,
+        };
+        let found = from.and_then(|f| {
+            find_in_history(hist, &search.query, f, search.reverse)
+        });
+        search.failed = found.is_none() && !search.query.is_empty();
+        search.idx = found.or(search.idx.filter(|_| !search.query.is_empty()));
+        self.show_search();
     }
 
-    fn insert(&mut self, s: &str) {
-        let len = self.buf.len();
-        self.buf.splice(self.pos..self.pos, s.chars());
-        self.reprint_from_move_to(self.pos, self.pos + self.buf.len() - len);
+    /// Show the search prompt and the matched entry.
+    fn show_search(&mut self) {
+        let search = self.search.as_ref().unwrap();
+        let prompt = format!(
+            "({}{}i-search)`{}': ",
+            if search.failed { "failed " } else { "" },
+            if search.reverse { "reverse-" } else { "" },
+            search.query,
+        );
+
+        let entry = search
+            .idx
+            .filter(|_| !search.query.is_empty())
+            .and_then(|i| self.term.history()?.get(i));
+        let (buf, pos) = match entry {
+            Some(e) => {
+                let pos = if search.reverse {
+                    e.rfind(&search.query)
+                } else {
+                    e.find(&search.query)
+                }
+                .unwrap_or_default();
+                let pos = e[..pos].chars().count();
+                let len = search.query.chars().count();
+                self.highlight = Some(pos..pos + len);
+                let ml = self.multiline;
+                (e.chars().filter(|c| is_allowed(*c, ml)).collect(), pos)
+            }
+            None => {
+                self.highlight = None;
+                search.saved.clone()
+            }
+        };
+
+        self.show_all(Some(prompt.into()), buf, pos);
     }
 
-    fn move_word_right(&mut self) {
+    /// End the search. If `accept` is `true`, the matched entry is kept,
+    /// otherwise the original buffer is restored.
+    fn end_search(&mut self, accept: bool) {
+        let Some(search) = self.search.take() else {
+            return;
+        };
+        self.highlight = None;
+        let (buf, pos) = if accept {
+            (self.buf.clone(), self.pos)
+        } else {
+            search.saved
+        };
+        self.show_all(Some(search.prompt), buf, pos);
+    }
+
+    /// Replace the prompt and the whole buffer and show it.
+    fn show_all(
+        &mut self,
+        prompt: Option<TermText<'p>>,
+        buf: Vec<char>,
+        pos: usize,
+    ) {
+        self.move_rd_dif((0, 0).into(), self.cur_pos());
+        if let Some(prompt) = prompt {
+            self.prompt = prompt;
+        }
+        self.replace_buf(buf);
+        self.reprint_with_prompt_dont_move();
+        self.move_to_pos(pos.min(self.buf.len()));
+    }
+
+    /// Replace the buffer and show it. Only the changed part is reprinted.
+    fn show_changed(&mut self, buf: Vec<char>, pos: usize) {
+        let from = self
+            .buf
+            .iter()
+            .zip(&buf)
+            .position(|(a, b)| a != b)
+            .unwrap_or(self.buf.len().min(buf.len()));
+        self.move_to_pos(from);
+        self.replace_buf(buf);
+        self.reprint_dont_move(from);
+        self.move_to_pos(pos.min(self.buf.len()));
+    }
+
+    /// Replace the whole buffer and show it.
+    fn show_buf(&mut self, buf: Vec<char>, pos: Option<usize>) {
+        self.move_to_pos(0);
+        self.replace_buf(buf);
+        self.reprint_dont_move(0);
+        self.move_to_pos(pos.unwrap_or(self.buf.len()).min(self.buf.len()));
+    }
+
+    /// Get the position of the end of the next word.
+    fn word_right(&self) -> usize {
         let mut pos = self.pos;
-        pos = pos.min(self.buf.len());
-        while pos < self.buf.len() && self.buf[pos].is_ascii_whitespace() {
+        while pos < self.buf.len() && self.buf[pos].is_whitespace() {
             pos += 1;
         }
-        while pos < self.buf.len() && !self.buf[pos].is_ascii_whitespace() {
+        while pos < self.buf.len() && !self.buf[pos].is_whitespace() {
             pos += 1;
         }
-        self.move_to_pos(pos);
+        pos
     }
 
-    fn move_word_left(&mut self) {
+    /// Get the position of the start of the next word.
+    fn word_start_right(&self) -> usize {
         let mut pos = self.pos;
-        pos = pos.saturating_sub(1);
-        while pos > 0 && self.buf[pos].is_ascii_whitespace() {
+        while pos < self.buf.len() && !self.buf[pos].is_whitespace() {
+            pos += 1;
+        }
+        while pos < self.buf.len() && self.buf[pos].is_whitespace() {
+            pos += 1;
+        }
+        pos
+    }
+
+    /// Get the position of the start of the previous word.
+    fn word_left(&self) -> usize {
+        let mut pos = self.pos;
+        while pos > 0 && self.buf[pos - 1].is_whitespace() {
             pos -= 1;
         }
-        while pos > 0 && !self.buf[pos].is_ascii_whitespace() {
+        while pos > 0 && !self.buf[pos - 1].is_whitespace() {
             pos -= 1;
         }
-        if pos < self.buf.len() && self.buf[pos].is_ascii_whitespace() {
-            pos += 1;
+        pos
+    }
+
+    /// Get the position of char at the given index in the buffer relative to
+    /// the start of the prompt.
+    fn pos_of(&self, idx: usize) -> Vec2 {
+        let start = self.size.pos_of_idx(self.prompt.display_width());
+        match self.echo {
+            Echo::Visible => self.buf[..idx]
+                .iter()
+                .collect::<String>()
+                .graphemes(true)
+                .fold(start, |p, g| self.advance(p, g)),
+            Echo::Hidden => start,
+            Echo::Mask(m) => {
+                let m = m.to_string();
+                (0..self.mask_cnt(idx)).fold(start, |p, _| self.advance(p, &m))
+            }
         }
-        self.move_to_pos(pos);
     }
 
-    /// Gets the position + prompt lentgth
-    fn len(&self) -> usize {
-        self.pos + self.prompt.display_char_cnt()
+    /// Get the number of mask chars shown for the chars before the index.
+    fn mask_cnt(&self, idx: usize) -> usize {
+        grapheme_ranges(&self.buf[..idx]).len()
+    }
+
+    /// Get the position after the given grapheme is printed at the position
+    /// `p`.
+    fn advance(&self, mut p: Vec2, g: &str) -> Vec2 {
+        if g == "\n" && self.multiline {
+            let cont = self
+                .size
+                .pos_of_idx(self.continuation_prompt.display_width());
+            return Vec2::new(cont.x, p.y + 1 + cont.y);
+        }
+
+        let w = grapheme_width(g);
+        if w == 0 {
+            return p;
+        }
+        // Wide character that doesn't fit is printed on the next line.
+        if p.x + w > self.size.x {
+            p.x = 0;
+            p.y += 1;
+        }
+        p.x += w;
+        if p.x >= self.size.x {
+            p.x = 0;
+            p.y += 1;
+        }
+        p
     }
 
     fn cur_pos(&self) -> Vec2 {
-        self.size.pos_of_idx(self.len())
+        self.pos_of(self.pos)
+    }
+
+    /// Get the index of the start of the line with the given position.
+    fn line_start(&self, pos: usize) -> usize {
+        if !self.multiline {
+            return 0;
+        }
+        self.buf[..pos]
+            .iter()
+            .rposition(|c| *c == '\n')
+            .map_or(0, |p| p + 1)
     }
 
-    fn start_pos(&self) -> Vec2 {
-        self.size.pos_of_idx(self.prompt.display_char_cnt())
+    /// Get the index of the end of the line with the given position.
+    fn line_end(&self, pos: usize) -> usize {
+        if !self.multiline {
+            return self.buf.len();
+        }
+        self.buf[pos..]
+            .iter()
+            .position(|c| *c == '\n')
+            .map_or(self.buf.len(), |p| p + pos)
     }
 
-    fn move_start(&mut self) {
-        self.move_rd_dif(self.start_pos(), self.cur_pos());
+    /// Move to the previous line in multi-line mode.
+    fn line_up(&mut self) {
+        let start = self.line_start(self.pos);
+        let col = chars_width(&self.buf[start..self.pos]);
+        let prev = self.line_start(start - 1);
+        self.move_to_pos(self.idx_at_col(prev, col));
     }
 
-    fn home(&mut self) {
-        self.move_start();
-        self.pos = 0;
+    /// Move to the next line in multi-line mode.
+    fn line_down(&mut self) {
+        let start = self.line_start(self.pos);
+        let col = chars_width(&self.buf[start..self.pos]);
+        let next = self.line_end(self.pos) + 1;
+        self.move_to_pos(self.idx_at_col(next, col));
+    }
+
+    /// Get index of the grapheme at the display column `col` in line that
+    /// starts at the index `start`.
+    fn idx_at_col(&self, start: usize, col: usize) -> usize {
+        let end = self.line_end(start);
+        let mut w = 0;
+        for r in grapheme_ranges(&self.buf[start..end]) {
+            w += chars_width(&self.buf[start + r.start..start + r.end]);
+            if w > col {
+                return start + r.start;
+            }
+        }
+        end
+    }
+
+    /// Get the index of the start of the next grapheme after `pos`.
+    fn next_grapheme(&self, pos: usize) -> usize {
+        grapheme_ranges(&self.buf)
+            .into_iter()
+            .map(|r| r.end)
+            .find(|e| *e > pos)
+            .unwrap_or(self.buf.len())
+    }
+
+    /// Get the index of the start of the grapheme before `pos`.
+    fn prev_grapheme(&self, pos: usize) -> usize {
+        grapheme_ranges(&self.buf)
+            .into_iter()
+            .map(|r| r.start)
+            .take_while(|s| *s < pos)
+            .last()
+            .unwrap_or_default()
     }
 
     fn end(&mut self) {
@@ -465,19 +1831,20 @@ where
 
     fn move_left(&mut self) {
         if self.pos != 0 {
-            self.move_to_pos(self.pos - 1);
+            self.move_to_pos(self.prev_grapheme(self.pos));
         }
     }
 
     fn move_right(&mut self) {
         if self.pos < self.buf.len() {
-            self.move_to_pos(self.pos + 1);
+            self.move_to_pos(self.next_grapheme(self.pos));
         }
     }
 
     fn delete(&mut self) {
         if self.pos < self.buf.len() {
-            self.buf.remove(self.pos);
+            let end = self.next_grapheme(self.pos);
+            self.buf.drain(self.pos..end);
             self.reprint_pos();
         }
     }
@@ -547,26 +1914,117 @@ where
         self.pbuf += codes::ERASE_TO_END;
         self.pbuf += self.prompt.as_str();
         self.print_from_dont_move(0);
-
         self.pos = self.buf.len();
-        if self.cur_pos().x == 0 && !self.buf.is_empty() {
-            self.pbuf += "\r\n";
-        }
     }
 
     fn reprint_dont_move(&mut self, pos: usize) {
         self.pbuf += codes::ERASE_TO_END;
         self.print_from_dont_move(pos);
-
         self.pos = self.buf.len();
-        if self.cur_pos().x == 0 && !self.buf.is_empty() {
-            self.pbuf += "\r\n";
-        }
     }
 
+    /// Print the buffer from the given position. The cursor must be at the
+    /// position. When the line wraps, the cursor is moved to the next line so
+    /// that its position is not ambiguous.
     fn print_from_dont_move(&mut self, pos: usize) {
-        self.pbuf
-            .extend(self.buf[pos..].iter().copied().map(get_printable));
+        let len = self.buf.len();
+        match self.echo {
+            Echo::Visible => {}
+            Echo::Hidden => return,
+            Echo::Mask(m) => {
+                let mut p = self.pos_of(pos);
+                let ms = m.to_string();
+                for _ in self.mask_cnt(pos)..self.mask_cnt(len) {
+                    p = self.advance(p, &ms);
+                    self.pbuf.push(m);
+                    if p.x == 0 {
+                        self.pbuf += "\r\n";
+                    }
+                }
+                return;
+            }
+        }
+
+        let mut pos = pos;
+        if self.highlighter.is_some() && self.highlighted != *self.buf {
+            // The change may affect highlighting of the text before `pos`.
+            if pos != 0 {
+                let (start, cur) = (self.pos_of(0), self.pos_of(pos));
+                self.move_rd_dif(start, cur);
+                pos = 0;
+            }
+            self.update_styles();
+        }
+        if self.highlighter.is_some() {
+            // The SGR state before `pos` is also needed.
+            for s in &self.styles[..pos] {
+                self.pbuf += s;
+            }
+        }
+
+        let hl = self.highlight.clone().unwrap_or_default();
+        let mut p = self.pos_of(pos);
+        for r in grapheme_ranges(&self.buf) {
+            if r.end <= pos {
+                continue;
+            }
+            let g: String = self.buf[r.clone()].iter().collect();
+            if r.start < pos {
+                // Only part of the grapheme is printed.
+                p = self.pos_of(r.start);
+            }
+            p = self.advance(p, &g);
+
+            for i in r.start.max(pos)..r.end {
+                if let Some(s) = self.styles.get(i) {
+                    self.pbuf += s;
+                }
+                if i == hl.start.max(pos) && i < hl.end {
+                    self.pbuf += codes::INVERSE;
+                }
+
+                let c = self.buf[i];
+                if c == '\n' && self.multiline {
+                    self.pbuf += "\r\n";
+                    self.pbuf += self.continuation_prompt.as_str();
+                } else {
+                    self.pbuf.push(get_printable(c));
+                }
+
+                if i + 1 == hl.end && i >= hl.start {
+                    self.pbuf += codes::RESET_INVERSE;
+                }
+            }
+
+            if p.x == 0 && g != "\n" {
+                self.pbuf += "\r\n";
+            }
+        }
+        if let Some(s) = self.styles.get(len) {
+            self.pbuf += s;
+        }
+    }
+
+    /// Highlight the buffer and store the SGR sequences in `styles`.
+    fn update_styles(&mut self) {
+        let Some(highlighter) = &mut self.highlighter else {
+            return;
+        };
+        let styled = highlighter.highlight(&self.buf);
+        let len = self.buf.len();
+        self.styles.clear();
+        self.styles.resize(len + 1, String::new());
+        let mut idx = 0;
+        for span in styled.spans() {
+            let text = span.text();
+            if !text.starts_with(codes::ESC) {
+                idx += span.chars();
+            } else if text.starts_with(codes::CSI) && text.ends_with('m') {
+                self.styles[idx.min(len)] += text;
+            }
+        }
+        self.styles[len] += codes::RESET;
+        self.highlighted.clone_from(&self.buf);
     }
 
     fn commit(&mut self) -> Result<()> {
@@ -579,6 +2037,158 @@ where
     }
 }
 
+/// Get the size of the terminal. Zero size is treated as unlimited.
+fn get_size(term: &Terminal<impl IoProvider>) -> Option<Vec2> {
+    let s = term.term_size().ok()?;
+    Some(
+        Vec2::new(s.char_width, s.char_height)
+            .map(|a| if a == 0 { usize::MAX } else { a }),
+    )
+}
+
+/// Replace the range in the buffer with the candidate. Returns position after
+/// the inserted text.
+fn apply_candidate(
+    buf: &mut Vec<char>,
+    cand: &Candidate,
+    filter: Option<&dyn InputFilter>,
+    multiline: bool,
+) -> usize {
+    buf.drain(cand.range.clone());
+    insert_chars(
+        buf,
+        cand.range.start,
+        cand.replacement.chars(),
+        filter,
+        multiline,
+    )
+}
+
+/// Insert the chars to the buffer at the position. Chars that are not allowed
+/// or that are rejected by the filter are skipped. Returns position after the
+/// inserted chars.
+fn insert_chars(
+    buf: &mut Vec<char>,
+    mut pos: usize,
+    chars: impl IntoIterator<Item = char>,
+    filter: Option<&dyn InputFilter>,
+    multiline: bool,
+) -> usize {
+    for c in chars {
+        if is_allowed(c, multiline)
+            && filter.is_none_or(|f| f.accept(buf, pos, c))
+        {
+            buf.insert(pos, c);
+            pos += 1;
+        }
+    }
+    pos
+}
+
+/// Find history entry that contains `query` starting at the index `from` in
+/// the given direction.
+fn find_in_history(
+    hist: &(dyn History + Send),
+    query: &str,
+    from: usize,
+    reverse: bool,
+) -> Option<usize> {
+    let matches = |i: &usize| hist.get(*i).is_some_and(|e| e.contains(query));
+    if reverse {
+        (0..=from.min(hist.len().checked_sub(1)?))
+            .rev()
+            .find(matches)
+    } else {
+        (from..hist.len()).find(matches)
+    }
+}
+
+/// Get ranges of graphemes in the buffer. The chars are not collected to
+/// string, so that no copy of hidden input is made.
+fn grapheme_ranges(buf: &[char]) -> Vec<Range<usize>> {
+    let len = buf.iter().map(|c| c.len_utf8()).sum();
+    let mut cur = GraphemeCursor::new(0, len, true);
+    let mut res = vec![];
+    let mut start = 0;
+    // Byte offset of the char at index `i`.
+    let mut offset = 0;
+    let mut i = 0;
+    while i < buf.len() {
+        let mut b = [0; 4];
+        let chunk = buf[i].encode_utf8(&mut b);
+        match cur.next_boundary(chunk, offset) {
+            // Boundary at the start of the char.
+            Ok(Some(b)) if b == offset => {
+                res.push(start..i);
+                start = i;
+                continue;
+            }
+            Ok(Some(_)) => {
+                res.push(start..i + 1);
+                start = i + 1;
+            }
+            Ok(None) => break,
+            Err(GraphemeIncomplete::PreContext(end)) => {
+                // Find the char that ends at `end`.
+                let (mut j, mut o) = (i, offset);
+                while o > end {
+                    j -= 1;
+                    o -= buf[j].len_utf8();
+                }
+                let mut b = [0; 4];
+                let ctx = buf[j - 1].encode_utf8(&mut b);
+                cur.provide_context(ctx, end - ctx.len());
+                continue;
+            }
+            Err(_) => {}
+        }
+        offset += chunk.len();
+        i += 1;
+    }
+    if start < buf.len() {
+        res.push(start..buf.len());
+    }
+    res
+}
+
+/// Overwrite the chars with zeros so that they don't stay in memory. Also
+/// the unused capacity is zeroed, because it may contain removed chars.
+fn zero_chars(buf: &mut Vec<char>) {
+    for c in buf.iter_mut() {
+        // Volatile write so that it is not optimized out.
+        unsafe { ptr::write_volatile(c, '\0') };
+    }
+    for c in buf.spare_capacity_mut() {
+        unsafe { ptr::write_volatile(c, mem::MaybeUninit::new('\0')) };
+    }
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
+/// Get the number of columns taken by the grapheme when it is printed.
+fn grapheme_width(g: &str) -> usize {
+    if g.chars().any(|c| c.is_control()) {
+        g.chars()
+            .map(|c| get_printable(c).width().unwrap_or_default())
+            .sum()
+    } else {
+        g.width()
+    }
+}
+
+/// Get the number of columns taken by the chars when they are printed.
+fn chars_width(buf: &[char]) -> usize {
+    buf.iter()
+        .collect::<String>()
+        .graphemes(true)
+        .map(grapheme_width)
+        .sum()
+}
+
+/// Checks whether the char may be in the buffer.
+fn is_allowed(c: char, multiline: bool) -> bool {
+    !c.is_ascii_control() || (multiline && c == '\n')
+}
+
 /// Get printable `non-control` character.
 pub fn get_printable(c: char) -> char {
     if !c.is_ascii_control() {
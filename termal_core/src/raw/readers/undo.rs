@@ -0,0 +1,73 @@
+/// Kind of change. Consecutive changes of the same group are undone at once.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UndoGroup {
+    Typing,
+    Completion,
+    Yank,
+    History,
+    Search,
+}
+
+/// Undo and redo stack of the buffer and cursor position.
+#[derive(Clone, Debug, Default)]
+pub struct UndoStack {
+    undo: Vec<(Vec<char>, usize)>,
+    redo: Vec<(Vec<char>, usize)>,
+    /// Group of the last change.
+    group: Option<UndoGroup>,
+}
+
+impl UndoStack {
+    /// Record change from the given state. The state is not saved if the
+    /// last change has the same group.
+    pub fn change(
+        &mut self,
+        buf: Vec<char>,
+        pos: usize,
+        group: Option<UndoGroup>,
+    ) {
+        self.redo.clear();
+        if group.is_none() || group != self.group {
+            self.undo.push((buf, pos));
+        }
+        self.group = group;
+    }
+
+    /// The next change will not be grouped with the previous one.
+    pub fn break_group(&mut self) {
+        self.group = None;
+    }
+
+    /// Get the state before the last change. The given current state may be
+    /// restored with redo.
+    pub fn undo(
+        &mut self,
+        buf: Vec<char>,
+        pos: usize,
+    ) -> Option<(Vec<char>, usize)> {
+        let res = self.undo.pop()?;
+        self.redo.push((buf, pos));
+        self.group = None;
+        Some(res)
+    }
+
+    /// Get the state before the last undo. The given current state may be
+    /// restored with undo.
+    pub fn redo(
+        &mut self,
+        buf: Vec<char>,
+        pos: usize,
+    ) -> Option<(Vec<char>, usize)> {
+        let res = self.redo.pop()?;
+        self.undo.push((buf, pos));
+        self.group = None;
+        Some(res)
+    }
+
+    /// Forget all the changes.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.group = None;
+    }
+}
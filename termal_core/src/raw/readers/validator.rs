@@ -0,0 +1,31 @@
+/// Result of [`Validator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Validation {
+    /// The input is complete and it may be submitted.
+    Complete,
+    /// The input is not complete. Enter will insert new line in multi-line
+    /// mode. In single-line mode, the input is not submitted and message is
+    /// shown below the input.
+    Incomplete,
+    /// The input is invalid and it cannot be submitted. The message is shown
+    /// below the input.
+    Invalid(String),
+}
+
+/// Decides whether the input of [`TermRead`] may be submitted, or whether it
+/// is complete in multi-line mode.
+///
+/// [`TermRead`]: super::TermRead
+pub trait Validator {
+    /// Validate the input.
+    fn validate(&self, buf: &[char]) -> Validation;
+}
+
+impl<F> Validator for F
+where
+    F: Fn(&[char]) -> Validation,
+{
+    fn validate(&self, buf: &[char]) -> Validation {
+        self(buf)
+    }
+}
@@ -3,7 +3,8 @@ use std::io::{IsTerminal, StdinLock, StdoutLock, stdin, stdout};
 use crate::error::Result;
 
 use super::{
-    IoProvider, ValueOrMut, WaitForIn, is_raw_mode_enabled, wait_for_stdin,
+    IoProvider, ValueOrMut, WaitForIn, WakeSignal, is_raw_mode_enabled,
+    wait_for_stdin, wait_for_stdin_or,
 };
 
 /// Zero size IoProvider with stdin and stdout.
@@ -14,6 +15,14 @@ impl WaitForIn for StdioProvider {
     fn wait_for_in(&self, timeout: std::time::Duration) -> Result<bool> {
         wait_for_stdin(timeout)
     }
+
+    fn wait_for_in_or(
+        &self,
+        signal: &WakeSignal,
+        timeout: std::time::Duration,
+    ) -> Result<bool> {
+        wait_for_stdin_or(signal, timeout)
+    }
 }
 
 impl IoProvider for StdioProvider {
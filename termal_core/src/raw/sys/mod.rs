@@ -2,8 +2,10 @@ use std::time::Duration;
 
 use crate::error::{Error, Result};
 
+use super::WakeSignal;
+
 #[cfg(unix)]
-mod unix;
+pub(crate) mod unix;
 #[cfg(windows)]
 mod windows;
 
@@ -105,3 +107,31 @@ pub fn wait_for_stdin(timeout: Duration) -> Result<bool> {
         Err(Error::NotSupportedOnPlatform("stdin timeout"))
     }
 }
+
+/// Wait for any event on stdin, but not longer than the timeout. The waiting
+/// may be interrupted with the signal.
+///
+/// # Returns
+/// `true` if there is event on stdin. If this returns due to timeout,
+/// interrupt or the signal, returns `false`.
+///
+/// # Support
+/// - Unix (Linux)
+/// - Windows (not tested)
+pub fn wait_for_stdin_or(
+    signal: &WakeSignal,
+    timeout: Duration,
+) -> Result<bool> {
+    if signal.is_set() {
+        return Ok(false);
+    }
+
+    #[cfg(unix)]
+    return unix::wait_for_stdin_or_fd(
+        std::os::fd::AsRawFd::as_raw_fd(signal),
+        timeout,
+    );
+
+    #[allow(unreachable_code)]
+    super::wake_signal::wait_sliced(signal, timeout, wait_for_stdin)
+}
@@ -1,6 +1,6 @@
 use std::{
     fs, io, mem,
-    os::fd::{AsRawFd, IntoRawFd, RawFd},
+    os::fd::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd, RawFd},
     sync::{Mutex, MutexGuard},
     time::Duration,
 };
@@ -133,10 +133,71 @@ pub(crate) fn wait_for_stdin(timeout: Duration) -> Result<bool> {
         revents: 0,
     };
 
-    let r = unsafe { poll(&mut pdfs, 1, timeout.as_millis() as i32) };
+    let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+    let r = unsafe { poll(&mut pdfs, 1, timeout) };
     Ok((r == 1 || r < 0) && r != EINTR)
 }
 
+/// Wait for stdin input or for input on the given fd on linux with the given
+/// timeout. Returns `true` only if there is input on stdin.
+pub(crate) fn wait_for_stdin_or_fd(
+    fd: RawFd,
+    timeout: Duration,
+) -> Result<bool> {
+    wait_for_fd_or_fd(libc::STDIN_FILENO, fd, timeout)
+}
+
+/// Wait for input on `fd` or on `other` on linux with the given timeout.
+/// Returns `true` only if there is input on `fd`.
+pub(crate) fn wait_for_fd_or_fd(
+    fd: RawFd,
+    other: RawFd,
+    timeout: Duration,
+) -> Result<bool> {
+    let mut pdfs = [
+        pollfd {
+            fd,
+            events: POLLIN,
+            revents: 0,
+        },
+        pollfd {
+            fd: other,
+            events: POLLIN,
+            revents: 0,
+        },
+    ];
+
+    let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+    let r = unsafe { poll(pdfs.as_mut_ptr(), 2, timeout) };
+    if r < 0 {
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() == Some(EINTR) {
+            return Ok(false);
+        }
+        return Err(err.into());
+    }
+
+    Ok(pdfs[0].revents != 0)
+}
+
+/// Create new pipe on linux. Returns the read and the write end.
+pub(crate) fn pipe() -> Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0; 2];
+    to_io_result(unsafe { libc::pipe(fds.as_mut_ptr()) })?;
+    Ok(
+        unsafe {
+            (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1]))
+        },
+    )
+}
+
+/// Write single byte to the given file descriptor.
+pub(crate) fn write_byte(fd: RawFd, b: u8) -> Result<()> {
+    let r = unsafe { libc::write(fd, (&b as *const u8).cast(), 1) };
+    to_io_result(r as i32)?;
+    Ok(())
+}
+
 fn get_terminal_attr(fd: RawFd) -> Result<Termios> {
     unsafe {
         let mut termios = mem::zeroed();
@@ -161,3 +222,24 @@ fn to_io_result(code: i32) -> io::Result<()> {
         Ok(())
     }
 }
+
+/// Wait for input on the given fd on linux with the given timeout.
+pub(crate) fn wait_for_fd(fd: RawFd, timeout: Duration) -> Result<bool> {
+    let mut pdfs = pollfd {
+        fd,
+        events: POLLIN,
+        revents: 0,
+    };
+
+    let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+    let r = unsafe { poll(&mut pdfs, 1, timeout) };
+    if r < 0 {
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() == Some(EINTR) {
+            return Ok(false);
+        }
+        return Err(err.into());
+    }
+
+    Ok(pdfs.revents != 0)
+}
@@ -79,7 +79,7 @@ pub fn wait_for_stdin(timeout: Duration) -> Result<bool> {
         MsgWaitForMultipleObjectsEx(
             1,
             &stdin,
-            timeout.as_millis() as DWORD,
+            timeout.as_millis().min(DWORD::MAX as u128 - 1) as DWORD,
             QS_ALLINPUT,
             MWMO_INPUTAVAILABLE,
         )
@@ -6,24 +6,94 @@ use std::{
 
 use crate::error::{Error, Result};
 
-use super::{IoProvider, StdioProvider, WaitForIn};
+use super::{IoProvider, StdioProvider, TermSize, WaitForIn, WakeSignal};
 
 #[cfg(feature = "events")]
 use crate::{
-    codes,
-    raw::events::{AmbigousEvent, AnyEvent, Event, StateChange},
+    codes::{self, Selection},
+    raw::events::{
+        AmbigousEvent, AnyEvent, Event, PasteMode, StateChange, Status, mouse,
+    },
 };
 #[cfg(feature = "readers")]
-use crate::{raw::readers::TermRead, term_text::TermText};
+use crate::{
+    raw::readers::{Echo, History, TermRead},
+    term_text::TermText,
+};
+#[cfg(feature = "async")]
+use std::{
+    future,
+    task::{Context, Poll},
+};
+
+#[cfg(feature = "events")]
+use super::ReaderHandle;
+#[cfg(all(unix, feature = "readers"))]
+use super::TtyProvider;
+#[cfg(feature = "async")]
+use super::{AsyncWaitForIn, EventStream};
+#[cfg(feature = "readers")]
+use super::{disable_raw_mode, enable_raw_mode, is_raw_mode_enabled};
+#[cfg(feature = "events")]
+use std::{
+    io, mem,
+    sync::{Arc, mpsc::Receiver},
+};
+
+/// Default maximum length of base64 encoded data written to clipboard. Most
+/// terminals accept at least this much.
+#[cfg(feature = "events")]
+pub const DEFAULT_CLIPBOARD_LIMIT: usize = 100_000;
 
 /// Terminal reader. Abstracts reading from terminal and parsing inputs. Works
 /// properly only if raw mode is enabled.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Terminal<T: IoProvider = StdioProvider> {
     buffer: VecDeque<u8>,
     io: T,
     #[cfg(feature = "events")]
     bracketed_paste_open: bool,
+    #[cfg(feature = "events")]
+    mouse_encoding: Option<mouse::Encoding>,
+    #[cfg(feature = "events")]
+    paste_mode: PasteMode,
+    /// Text of paste that is being collected.
+    #[cfg(feature = "events")]
+    paste: String,
+    /// The paste didn't fit into single event and it is streamed.
+    #[cfg(feature = "events")]
+    paste_streaming: bool,
+    /// Events that were already parsed, but not yet returned.
+    #[cfg(feature = "events")]
+    pending: VecDeque<AmbigousEvent>,
+    /// Maximum length of base64 encoded data written to clipboard.
+    #[cfg(feature = "events")]
+    clipboard_limit: Option<usize>,
+    /// History of inputs read with [`TermRead`].
+    #[cfg(feature = "readers")]
+    history: Option<Box<dyn History + Send>>,
+    /// How to wait for more input in the middle of event.
+    #[cfg(feature = "events")]
+    input_wait: InputWait,
+    /// Input consumed while parsing event that may not be finished. It is
+    /// collected only if `input_wait` is not [`InputWait::Block`].
+    #[cfg(feature = "events")]
+    read_log: Vec<u8>,
+}
+
+/// How [`Terminal`] waits for more input in the middle of event.
+#[cfg(feature = "events")]
+#[derive(Debug)]
+pub(crate) enum InputWait {
+    /// Block until there is input.
+    Block,
+    /// Don't wait. Reading fails with [`io::ErrorKind::WouldBlock`] if there
+    /// is no input.
+    #[cfg(feature = "async")]
+    NonBlocking,
+    /// Block until there is input or until the signal is set. Reading fails
+    /// with [`io::ErrorKind::WouldBlock`] if the signal is set.
+    Signal(Arc<WakeSignal>),
 }
 
 impl Terminal<StdioProvider> {
@@ -32,6 +102,45 @@ impl Terminal<StdioProvider> {
     }
 }
 
+impl<T: IoProvider + Default> Default for Terminal<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[cfg(feature = "readers")]
+impl Terminal<StdioProvider> {
+    /// Prompt the user for password. Nothing is echoed. If stdin is not
+    /// terminal, the password is read from the controlling terminal
+    /// (`/dev/tty`). Raw mode is enabled while prompting if it is not
+    /// enabled.
+    pub fn prompt_password<'a>(
+        &mut self,
+        s: impl Into<TermText<'a>>,
+    ) -> Result<String> {
+        let raw = is_raw_mode_enabled();
+        if !raw {
+            enable_raw_mode()?;
+        }
+
+        #[cfg(unix)]
+        let r = if self.is_in_terminal() {
+            self.prompt_masked(s, Echo::Hidden)
+        } else {
+            TtyProvider::open()
+                .and_then(|t| Terminal::new(t).prompt_masked(s, Echo::Hidden))
+        };
+        #[cfg(not(unix))]
+        let r = self.prompt_masked(s, Echo::Hidden);
+
+        if !raw {
+            _ = disable_raw_mode();
+        }
+
+        r
+    }
+}
+
 impl<T: IoProvider> Terminal<T> {
     /// Create new terminal.
     pub fn new(io: T) -> Self {
@@ -40,20 +149,61 @@ impl<T: IoProvider> Terminal<T> {
             io,
             #[cfg(feature = "events")]
             bracketed_paste_open: false,
+            #[cfg(feature = "events")]
+            mouse_encoding: None,
+            #[cfg(feature = "events")]
+            paste_mode: PasteMode::Chars,
+            #[cfg(feature = "events")]
+            paste: String::new(),
+            #[cfg(feature = "events")]
+            paste_streaming: false,
+            #[cfg(feature = "events")]
+            pending: VecDeque::new(),
+            #[cfg(feature = "events")]
+            clipboard_limit: Some(DEFAULT_CLIPBOARD_LIMIT),
+            #[cfg(feature = "readers")]
+            history: None,
+            #[cfg(feature = "events")]
+            input_wait: InputWait::Block,
+            #[cfg(feature = "events")]
+            read_log: vec![],
         }
     }
 
+    /// Get the underlying io provider.
+    pub fn io(&self) -> &T {
+        &self.io
+    }
+
+    /// Get the underlying io provider.
+    pub fn io_mut(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    /// Get the size of the terminal.
+    pub fn term_size(&self) -> Result<TermSize> {
+        self.io.term_size()
+    }
+
     /// Read next byte from stdin. May block.
     pub fn read_byte(&mut self) -> Result<u8> {
-        if let Some(b) = self.buffer.pop_front() {
-            return Ok(b);
+        if self.buffer.is_empty() {
+            self.fill_buffer()?;
+        }
+        let b = self.buffer.pop_front().ok_or(Error::StdInEof)?;
+        #[cfg(feature = "events")]
+        if !matches!(self.input_wait, InputWait::Block) {
+            self.read_log.push(b);
         }
-        self.fill_buffer()?;
-        self.buffer.pop_front().ok_or(Error::StdInEof)
+        Ok(b)
     }
 
     /// Checks whether there is any buffered input in [`Terminal`]
     pub fn has_buffered_input(&self) -> bool {
+        #[cfg(feature = "events")]
+        if !self.pending.is_empty() {
+            return true;
+        }
         !self.buffer.is_empty()
     }
 
@@ -72,6 +222,20 @@ impl<T: IoProvider> Terminal<T> {
         }
     }
 
+    /// Wait for input on the terminal. Block for at most the given duration.
+    /// The waiting may be interrupted with the signal.
+    pub fn wait_for_input_or(
+        &self,
+        signal: &WakeSignal,
+        timeout: Duration,
+    ) -> Result<bool> {
+        if self.has_buffered_input() {
+            Ok(true)
+        } else {
+            self.io.wait_for_in_or(signal, timeout)
+        }
+    }
+
     /// Read raw bytes from the terminal to `res`. Returns the number of readed
     /// bytes. Returns [`Error::StdInEof`] when reaches eof. May block.
     pub fn read_raw(&mut self, res: &mut [u8]) -> Result<usize> {
@@ -186,6 +350,9 @@ impl<T: IoProvider> Terminal<T> {
     }
 
     fn fill_buffer(&mut self) -> Result<()> {
+        #[cfg(feature = "events")]
+        self.wait_for_fill()?;
+
         let mut stdin = self.io.get_in();
         let buf = stdin.fill_buf()?;
         self.buffer.extend(buf);
@@ -244,6 +411,46 @@ impl<T: IoProvider> Terminal<T> {
         reader.set_prompt(prompt);
         reader.read_to_str(s)
     }
+
+    /// Prompt the user with the given prompt and return the entered result.
+    /// The input is shown as specified by `echo` and it is not added to
+    /// history.
+    pub fn prompt_masked<'a>(
+        &mut self,
+        s: impl Into<TermText<'a>>,
+        echo: Echo,
+    ) -> Result<String> {
+        let mut reader = TermRead::lines(self);
+        reader.set_prompt(s);
+        reader.set_echo(echo);
+        reader.read_str()
+    }
+
+    /// Set the history used by [`TermRead`] (and so also by
+    /// [`Terminal::prompt`] and similar). Accepted inputs are added to it and
+    /// it may be navigated with the up and down arrows. There is no history
+    /// by default.
+    pub fn set_history(&mut self, history: impl History + Send + 'static) {
+        self.history = Some(Box::new(history));
+    }
+
+    /// Get the history used by [`TermRead`].
+    pub fn history(&self) -> Option<&(dyn History + Send)> {
+        self.history.as_deref()
+    }
+
+    /// Get the history used by [`TermRead`].
+    pub fn history_mut(
+        &mut self,
+    ) -> Option<&mut (dyn History + Send + 'static)> {
+        self.history.as_deref_mut()
+    }
+
+    /// Remove the history from the terminal and return it. This disables the
+    /// history.
+    pub fn take_history(&mut self) -> Option<Box<dyn History + Send>> {
+        self.history.take()
+    }
 }
 
 #[cfg(feature = "events")]
@@ -272,8 +479,13 @@ impl<T: IoProvider> Terminal<T> {
 
     /// Read the next event on stdin. May block.
     pub fn read_ambigous(&mut self) -> Result<AmbigousEvent> {
-        if self.bracketed_paste_open {
-            self.read_bracketed()
+        if let Some(evt) = self.pending.pop_front() {
+            Ok(evt)
+        } else if self.bracketed_paste_open {
+            match self.paste_mode {
+                PasteMode::Chars => self.read_bracketed(),
+                PasteMode::Collect { max_len } => self.read_paste(max_len),
+            }
         } else if self.cur()? == 0x1b && self.buffer.len() != 1 {
             self.read_escape()
         } else {
@@ -346,6 +558,140 @@ impl<T: IoProvider> Terminal<T> {
         self.bracketed_paste_open
     }
 
+    /// Set how bracketed paste is read. Default is [`PasteMode::Chars`] which
+    /// produces single event for each character of the paste.
+    ///
+    /// With [`PasteMode::Collect`], the whole paste is read as single
+    /// [`Event::Paste`]. Pastes longer than the limit are streamed in multiple
+    /// [`Event::Paste`] events surrounded by
+    /// [`StateChange::BracketedPasteStart`] and
+    /// [`StateChange::BracketedPasteEnd`].
+    pub fn set_paste_mode(&mut self, mode: PasteMode) {
+        self.paste_mode = mode;
+    }
+
+    /// Get the mode in which bracketed paste is read.
+    pub fn paste_mode(&self) -> PasteMode {
+        self.paste_mode
+    }
+
+    /// Set the encoding of mouse events that is enabled in the terminal. It is
+    /// used to properly decode the mouse events. `None` means that the
+    /// encoding is not known and it will be guessed. Default is `None`.
+    ///
+    /// If the encoding is [`mouse::Encoding::SgrPixels`], mouse events will
+    /// have their position in pixels.
+    pub fn set_mouse_encoding(&mut self, encoding: Option<mouse::Encoding>) {
+        self.mouse_encoding = encoding;
+    }
+
+    /// Get the encoding of mouse events that is enabled in the terminal. `None`
+    /// means that it is not known.
+    pub fn mouse_encoding(&self) -> Option<mouse::Encoding> {
+        self.mouse_encoding
+    }
+
+    /// Enable the given encoding of mouse events in the terminal and track it.
+    /// The previously enabled encoding (if known) is disabled.
+    pub fn enable_mouse_encoding(
+        &mut self,
+        encoding: mouse::Encoding,
+    ) -> Result<()> {
+        if let Some(old) = self.mouse_encoding {
+            self.write_all(old.disable_code().as_bytes())?;
+        }
+        self.write_all(encoding.enable_code().as_bytes())?;
+        self.flush()?;
+        self.mouse_encoding = Some(encoding);
+        Ok(())
+    }
+
+    /// Set the maximum length of base64 encoded data that may be written to
+    /// clipboard with [`Terminal::copy_to_clipboard`]. `None` means no limit.
+    /// Default is [`DEFAULT_CLIPBOARD_LIMIT`].
+    ///
+    /// Terminals usually silently ignore too long clipboard data.
+    pub fn set_clipboard_limit(&mut self, limit: Option<usize>) {
+        self.clipboard_limit = limit;
+    }
+
+    /// Get the maximum length of base64 encoded data that may be written to
+    /// clipboard. `None` means no limit.
+    pub fn clipboard_limit(&self) -> Option<usize> {
+        self.clipboard_limit
+    }
+
+    /// Set the selection buffer to the given text. Returns
+    /// [`Error::ClipboardTooLarge`] if the encoded text exceeds the clipboard
+    /// limit (see [`Terminal::set_clipboard_limit`]). Nothing is written in
+    /// such case.
+    ///
+    /// The terminal doesn't confirm the change, so there is no way to know
+    /// whether it has succeeded.
+    pub fn copy_to_clipboard(
+        &mut self,
+        sel: Selection,
+        s: &str,
+    ) -> Result<()> {
+        let len = s.len().div_ceil(3) * 4;
+        if let Some(max) = self.clipboard_limit.filter(|m| len > *m) {
+            return Err(Error::ClipboardTooLarge { len, max });
+        }
+        self.write_all(codes::set_selection([sel], s).as_bytes())?;
+        self.flush()?;
+        Ok(())
+    }
+
+    /// Read the contents of the selection buffer. Block for at most the given
+    /// duration. Returns `None` if the terminal doesn't reply in time.
+    ///
+    /// The request is followed by request for device attributes which all
+    /// terminals reply to. If the terminal replies to it without sending the
+    /// selection data, it doesn't support reading the selection (or it is
+    /// disabled) and [`Error::ClipboardRefused`] is returned.
+    ///
+    /// Other events received while waiting are not lost, they will be
+    /// returned by the next reads of events.
+    pub fn read_clipboard(
+        &mut self,
+        sel: Selection,
+        timeout: Duration,
+    ) -> Result<Option<String>> {
+        self.write_all(codes::request_selection([sel]).as_bytes())?;
+        self.write_all(codes::REQUEST_DEVICE_ATTRIBUTES.as_bytes())?;
+        self.flush()?;
+
+        let start = Instant::now();
+        let mut skipped = vec![];
+        let mut data = None;
+        let res = loop {
+            let remaining = timeout.saturating_sub(start.elapsed());
+            let evt = match self.read_ambigous_timeout(remaining) {
+                Ok(Some(e)) => e,
+                // The reply to device attributes may be lost, but the data is
+                // already here.
+                Ok(None) => break Ok(data),
+                Err(e) => break Err(e),
+            };
+            match evt.event {
+                AnyEvent::Known(Event::Status(Status::SelectionData(d)))
+                    if data.is_none() =>
+                {
+                    data = Some(String::from_utf8_lossy(&d).into_owned());
+                }
+                AnyEvent::Known(Event::Status(Status::Attributes(_))) => {
+                    break data.map(Some).ok_or(Error::ClipboardRefused);
+                }
+                _ => skipped.push(evt),
+            }
+        };
+
+        for evt in skipped.into_iter().rev() {
+            self.pending.push_front(evt);
+        }
+        res
+    }
+
     fn read_escape(&mut self) -> Result<AmbigousEvent> {
         self.read_byte()?;
         let cur = self.cur()?;
@@ -368,7 +714,8 @@ impl<T: IoProvider> Terminal<T> {
 
         if cur == b'M' {
             // Special mouse event that actually doesn't conform to CSI
-            // sequence rules.
+            // sequence rules. Each value is either single byte or two byte
+            // UTF-8 character (UTF-8 extension).
             code.push(cur);
             for _ in 0..3 {
                 if self.buffer.is_empty() {
@@ -378,22 +725,22 @@ impl<T: IoProvider> Terminal<T> {
                     return Ok(AmbigousEvent::from_code(&code));
                 };
                 code.push(b);
-            }
-            if self.buffer.is_empty() {
-                return Ok(AmbigousEvent::from_code(&code));
-            }
-            // UTF-8 extension
-            for i in (1..=3).rev() {
-                if !self.buffer.is_empty()
-                    && utf8_code_len(code[code.len() - i]) != 2
+                if self
+                    .mouse_encoding
+                    .is_none_or(|e| e == mouse::Encoding::Utf8)
+                    && (0xC2..=0xDF).contains(&b)
+                    && self
+                        .buffer
+                        .front()
+                        .is_some_and(|b| (0x80..=0xBF).contains(b))
                 {
-                    let Some(b) = self.read_byte_if(|b| b >= 32)? else {
-                        return Ok(AmbigousEvent::from_code(&code));
-                    };
-                    code.push(b);
+                    code.push(self.read_byte()?);
                 }
             }
-            return Ok(AmbigousEvent::from_code(&code));
+            return Ok(AmbigousEvent::from_code_mouse(
+                &code,
+                self.mouse_encoding,
+            ));
         }
 
         while (0x30..=0x3F).contains(&cur) {
@@ -409,11 +756,14 @@ impl<T: IoProvider> Terminal<T> {
         code.push(cur);
         if code == codes::BRACKETED_PASTE_START.as_bytes() {
             self.bracketed_paste_open = true;
-            Ok(AmbigousEvent::state_change(
-                StateChange::BracketedPasteStart,
-            ))
+            match self.paste_mode {
+                PasteMode::Chars => Ok(AmbigousEvent::state_change(
+                    StateChange::BracketedPasteStart,
+                )),
+                PasteMode::Collect { max_len } => self.read_paste(max_len),
+            }
         } else {
-            Ok(AmbigousEvent::from_code(&code))
+            Ok(AmbigousEvent::from_code_mouse(&code, self.mouse_encoding))
         }
     }
 
@@ -486,12 +836,12 @@ impl<T: IoProvider> Terminal<T> {
 
     fn read_bracketed(&mut self) -> Result<AmbigousEvent> {
         let c = self.cur()?;
-        if self.buffer_starts_with(codes::BRACKETED_PASTE_END.as_bytes()) {
-            self.buffer.consume(codes::BRACKETED_PASTE_END.len());
+        if self.at_paste_end()? {
+            self.consume_buffer(codes::BRACKETED_PASTE_END.len());
             self.bracketed_paste_open = false;
             Ok(AmbigousEvent::state_change(StateChange::BracketedPasteEnd))
         } else if c.is_ascii() {
-            self.buffer.consume(1);
+            self.consume_buffer(1);
             if c == 0xD {
                 Ok(AmbigousEvent::verbatim('\n'))
             } else {
@@ -503,6 +853,137 @@ impl<T: IoProvider> Terminal<T> {
         }
     }
 
+    fn read_paste(&mut self, max_len: usize) -> Result<AmbigousEvent> {
+        loop {
+            match self.cur() {
+                Ok(_) => {}
+                // Don't lose the collected text.
+                Err(Error::StdInEof) if !self.paste.is_empty() => {
+                    return Ok(self.take_paste());
+                }
+                Err(e) => return Err(e),
+            }
+
+            if self.at_paste_end()? {
+                // Finish the last chunk before the end of streamed paste.
+                if self.paste_streaming && !self.paste.is_empty() {
+                    return Ok(self.take_paste());
+                }
+                self.consume_buffer(codes::BRACKETED_PASTE_END.len());
+                self.bracketed_paste_open = false;
+                if self.paste_streaming {
+                    self.paste_streaming = false;
+                    return Ok(AmbigousEvent::state_change(
+                        StateChange::BracketedPasteEnd,
+                    ));
+                }
+                return Ok(self.take_paste());
+            }
+
+            if self.paste.len() >= max_len.max(1) {
+                if self.paste_streaming {
+                    return Ok(self.take_paste());
+                }
+                // The paste is too long, fallback to streaming. The collected
+                // text will be the first chunk.
+                self.paste_streaming = true;
+                return Ok(AmbigousEvent::state_change(
+                    StateChange::BracketedPasteStart,
+                ));
+            }
+
+            let c = if self.cur()?.is_ascii() {
+                self.read_byte()? as char
+            } else {
+                let mut buf: [u8; 4] = [0; 4];
+                self.read_utf8(&mut buf)?
+            };
+            self.paste.push(if c == '\r' { '\n' } else { c });
+        }
+    }
+
+    /// Read the next event, but wait for the rest of the event only as
+    /// allowed by `wait`. Returns `None` if the waiting was not allowed. The
+    /// input of the unfinished event is not consumed in such case, except for
+    /// the text of bracketed paste, which is kept and continued with the next
+    /// read.
+    pub(crate) fn try_read_ambigous(
+        &mut self,
+        wait: InputWait,
+    ) -> Result<Option<AmbigousEvent>> {
+        let in_paste = self.bracketed_paste_open;
+        self.input_wait = wait;
+        let res = self.read_ambigous();
+        self.input_wait = InputWait::Block;
+
+        let res = match res {
+            Err(Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => {
+                // Return the consumed input of the unfinished event back to
+                // the buffer.
+                if !in_paste && !self.bracketed_paste_open {
+                    for b in self.read_log.iter().rev() {
+                        self.buffer.push_front(*b);
+                    }
+                }
+                Ok(None)
+            }
+            r => r.map(Some),
+        };
+        self.read_log.clear();
+        res
+    }
+
+    /// Wait for more input before filling the buffer as specified by
+    /// `input_wait`.
+    fn wait_for_fill(&self) -> Result<()> {
+        match self.input_wait {
+            InputWait::Block => Ok(()),
+            #[cfg(feature = "async")]
+            InputWait::NonBlocking => {
+                if self.io.wait_for_in(Duration::ZERO)? {
+                    Ok(())
+                } else {
+                    Err(io::Error::from(io::ErrorKind::WouldBlock).into())
+                }
+            }
+            InputWait::Signal(ref signal) => {
+                while !self.io.wait_for_in_or(signal, Duration::MAX)? {
+                    if signal.is_set() {
+                        return Err(io::Error::from(
+                            io::ErrorKind::WouldBlock,
+                        )
+                        .into());
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn take_paste(&mut self) -> AmbigousEvent {
+        AmbigousEvent::paste(mem::take(&mut self.paste))
+    }
+
+    /// Checks whether the buffer starts with the end of bracketed paste. If
+    /// the buffered input is only start of the end, more input is read.
+    fn at_paste_end(&mut self) -> Result<bool> {
+        let end = codes::BRACKETED_PASTE_END.as_bytes();
+        loop {
+            if self.buffer_starts_with(end) {
+                return Ok(true);
+            }
+            let len = self.buffer.len();
+            if len >= end.len() || !self.buffer.iter().eq(&end[..len]) {
+                return Ok(false);
+            }
+            self.fill_buffer()?;
+            if self.buffer.len() == len {
+                // Eof
+                return Ok(false);
+            }
+        }
+    }
+
     fn buffer_starts_with(&self, b: &[u8]) -> bool {
         if self.buffer.len() < b.len() {
             return false;
@@ -536,7 +1017,7 @@ impl<T: IoProvider> Terminal<T> {
 
             buf[i - 1] = self.buffer[i - 1];
             if let Ok(code) = std::str::from_utf8(&buf[..i]) {
-                self.buffer.consume(i);
+                self.consume_buffer(i);
                 return Ok(code.chars().next().unwrap());
             }
         }
@@ -549,9 +1030,85 @@ impl<T: IoProvider> Terminal<T> {
             Ok(Some(c))
         } else {
             self.buffer.push_front(c);
+            self.read_log.pop();
             Ok(None)
         }
     }
+
+    /// Consume `n` bytes from the buffer. The bytes are logged if the event
+    /// may not be finished.
+    fn consume_buffer(&mut self, n: usize) {
+        if !matches!(self.input_wait, InputWait::Block) {
+            let (a, b) = self.buffer.as_slices();
+            let a = &a[..n.min(a.len())];
+            self.read_log.extend(a);
+            self.read_log.extend(&b[..n - a.len()]);
+        }
+        self.buffer.consume(n);
+    }
+}
+
+#[cfg(feature = "events")]
+impl<T: IoProvider + Send + 'static> Terminal<T> {
+    /// Move reading of events to a background thread. Returns receiver of the
+    /// events and handle that can stop the thread and return back the
+    /// terminal. Replies to queries can be received with the handle. See
+    /// [`ReaderHandle`] for more info.
+    pub fn spawn_reader(self) -> Result<(Receiver<Event>, ReaderHandle<T>)> {
+        ReaderHandle::spawn(self)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: IoProvider + AsyncWaitForIn> Terminal<T> {
+    /// Try to read the next known event. If there is no input available,
+    /// returns [`Poll::Pending`] and the task will be woken once there is
+    /// input. Returns `None` when reaches eof.
+    ///
+    /// The parsing is the same as with [`Terminal::read`].
+    pub fn poll_read(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Event>>> {
+        // Wait for input only if there is no buffered input or if the
+        // buffered input is only start of event.
+        let mut wait = !self.has_buffered_input();
+        loop {
+            if wait {
+                match self.io.poll_for_in(cx) {
+                    Poll::Ready(Ok(_)) => {}
+                    Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+
+            match self.try_read_ambigous(InputWait::NonBlocking) {
+                Ok(Some(AmbigousEvent {
+                    event: AnyEvent::Known(ev),
+                    ..
+                })) => return Poll::Ready(Some(Ok(ev))),
+                Ok(Some(_)) => wait = !self.has_buffered_input(),
+                Ok(None) => wait = true,
+                Err(Error::StdInEof) => return Poll::Ready(None),
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            }
+        }
+    }
+
+    /// Asynchronously read the next known event. Returns
+    /// [`Error::StdInEof`] when reaches eof.
+    pub async fn read_async(&mut self) -> Result<Event> {
+        // The stream stops waiting for input if the future is dropped.
+        let mut stream = self.event_stream();
+        future::poll_fn(|cx| stream.terminal().poll_read(cx))
+            .await
+            .unwrap_or(Err(Error::StdInEof))
+    }
+
+    /// Get asynchronous stream of known events.
+    pub fn event_stream(&mut self) -> EventStream<'_, T> {
+        EventStream::new(self)
+    }
 }
 
 impl<T: IoProvider> Read for Terminal<T> {
@@ -580,18 +1137,3 @@ fn read_stdin_once(stdin: &mut impl BufRead, res: &mut [u8]) -> Result<usize> {
     stdin.consume(len);
     Ok(len)
 }
-
-#[cfg(feature = "events")]
-fn utf8_code_len(first: u8) -> usize {
-    if (first & 0x80) == 0 {
-        1
-    } else if (first & 0xE0) == 0xC0 {
-        2
-    } else if (first & 0xF0) == 0xE0 {
-        3
-    } else if (first & 0xF8) == 0xF0 {
-        4
-    } else {
-        0
-    }
-}
@@ -0,0 +1,110 @@
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    io::{BufRead, Read},
+    time::Duration,
+};
+
+use crate::error::Result;
+
+use super::WaitForIn;
+
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+
+/// Scripted input for testing. Each chunk of input is returned by separate
+/// read, so that it may be distinguished for example when reading escape
+/// key.
+///
+/// Chunks may be delayed by gaps. The time is simulated: waiting for input
+/// with timeout shorter than the remaining gap returns `false` and shortens
+/// the gap by the timeout. Blocking read doesn't wait for the gap. Polling for
+/// input returns pending once for each gap and the gap passes.
+#[derive(Debug, Clone, Default)]
+pub struct TestInput {
+    /// Chunks of input and the remaining gaps before them.
+    chunks: VecDeque<(Cell<Duration>, Vec<u8>)>,
+    pos: usize,
+    /// Gap before the next pushed chunk.
+    gap: Duration,
+}
+
+impl TestInput {
+    /// Add chunk of input.
+    pub fn push(&mut self, data: impl Into<Vec<u8>>) {
+        let data = data.into();
+        if !data.is_empty() {
+            let gap = std::mem::take(&mut self.gap);
+            self.chunks.push_back((Cell::new(gap), data));
+        }
+    }
+
+    /// Add gap before the next chunk of input.
+    pub fn push_gap(&mut self, gap: Duration) {
+        self.gap += gap;
+    }
+
+    /// Checks whether there is any remaining input.
+    pub fn has_data(&self) -> bool {
+        !self.chunks.is_empty()
+    }
+}
+
+impl Read for TestInput {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let data = self.fill_buf()?;
+        let len = buf.len().min(data.len());
+        buf[..len].copy_from_slice(&data[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl BufRead for TestInput {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.chunks.front().map_or(&[], |(_, c)| &c[self.pos..]))
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let Some((_, chunk)) = self.chunks.front() else {
+            return;
+        };
+        self.pos += amt;
+        if self.pos >= chunk.len() {
+            self.pos = 0;
+            self.chunks.pop_front();
+        }
+    }
+}
+
+impl WaitForIn for TestInput {
+    /// Checks whether the gap before the next chunk passes within the
+    /// timeout. When there is no more input, this returns `true` and reading
+    /// returns eof.
+    fn wait_for_in(&self, timeout: Duration) -> Result<bool> {
+        let Some((gap, _)) = self.chunks.front() else {
+            return Ok(true);
+        };
+        if gap.get() <= timeout {
+            gap.set(Duration::ZERO);
+            Ok(true)
+        } else {
+            gap.set(gap.get() - timeout);
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl super::AsyncWaitForIn for TestInput {
+    fn poll_for_in(&self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match self.chunks.front() {
+            Some((gap, _)) if !gap.get().is_zero() => {
+                gap.set(Duration::ZERO);
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+mod screen;
+mod screen_cell;
+
+pub use self::{screen::*, screen_cell::*};
+
+use std::time::Duration;
+
+use crate::error::Result;
+
+use super::{IoProvider, TermSize, TestInput, ValueOrMut, WaitForIn};
+
+/// Virtual terminal for testing. It implements [`IoProvider`] that reads
+/// scripted input and interprets the output into [`Screen`] that may be
+/// inspected.
+///
+/// # Example
+/// ```
+/// use termal_core::raw::{Terminal, TestTerminal};
+///
+/// let mut term = Terminal::new(TestTerminal::new(20, 3).input(b"hi\r"));
+/// let line = term.prompt("> ").unwrap();
+///
+/// assert_eq!(line, "hi");
+/// term.io().screen().assert_text("> hi");
+/// ```
+#[derive(Debug, Clone)]
+pub struct TestTerminal {
+    screen: Screen,
+    input: TestInput,
+}
+
+impl TestTerminal {
+    /// Create virtual terminal with the given size and no input.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            screen: Screen::new(width, height),
+            input: TestInput::default(),
+        }
+    }
+
+    /// Add chunk of input.
+    pub fn input(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.push_input(data);
+        self
+    }
+
+    /// Add chunk of input.
+    pub fn push_input(&mut self, data: impl Into<Vec<u8>>) {
+        self.input.push(data);
+    }
+
+    /// Add gap before the next chunk of input. See [`TestInput`].
+    pub fn gap(mut self, gap: Duration) -> Self {
+        self.input.push_gap(gap);
+        self
+    }
+
+    /// Checks whether there is any remaining input.
+    pub fn has_input(&self) -> bool {
+        self.input.has_data()
+    }
+
+    /// Get the screen with the output.
+    pub fn screen(&self) -> &Screen {
+        &self.screen
+    }
+
+    /// Get the screen with the output.
+    pub fn screen_mut(&mut self) -> &mut Screen {
+        &mut self.screen
+    }
+}
+
+impl WaitForIn for TestTerminal {
+    fn wait_for_in(&self, timeout: Duration) -> Result<bool> {
+        self.input.wait_for_in(timeout)
+    }
+}
+
+#[cfg(feature = "async")]
+impl super::AsyncWaitForIn for TestTerminal {
+    fn poll_for_in(
+        &self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<()>> {
+        super::AsyncWaitForIn::poll_for_in(&self.input, cx)
+    }
+}
+
+impl IoProvider for TestTerminal {
+    type Out = Screen;
+    type In = TestInput;
+
+    fn get_out(&mut self) -> ValueOrMut<'_, Self::Out> {
+        ValueOrMut::Mut(&mut self.screen)
+    }
+
+    fn get_in(&mut self) -> ValueOrMut<'_, Self::In> {
+        ValueOrMut::Mut(&mut self.input)
+    }
+
+    fn is_out_terminal(&self) -> bool {
+        true
+    }
+
+    fn is_in_terminal(&self) -> bool {
+        true
+    }
+
+    fn is_out_raw(&self) -> bool {
+        true
+    }
+
+    fn term_size(&self) -> Result<TermSize> {
+        Ok(TermSize {
+            char_width: self.screen.width(),
+            char_height: self.screen.height(),
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+    }
+}
@@ -0,0 +1,619 @@
+use std::{io::Write, mem};
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::{codes, term_text::TextStyle};
+
+use super::ScreenCell;
+
+/// Tab stops are at every multiple of this.
+const TAB_WIDTH: usize = 8;
+/// Zero width joiner. Char after it is part of the same grapheme cluster.
+const ZWJ: char = '\u{200d}';
+
+/// Grid of cells that interprets the output written to it like terminal.
+///
+/// Supported are printable characters (including wide characters and
+/// grapheme clusters), line wrapping, `\r`, `\n`, `\t`, backspace, cursor
+/// movement, erasing, inserting and deleting characters and lines,
+/// scrolling, scroll regions, SGR, saving the cursor, cursor visibility and
+/// the alternate buffer. Other sequences are ignored.
+///
+/// Newline `\n` only moves the cursor down as in raw mode.
+#[derive(Debug, Clone)]
+pub struct Screen {
+    width: usize,
+    height: usize,
+    lines: Vec<Vec<ScreenCell>>,
+    /// Lines of the inactive buffer.
+    other: Vec<Vec<ScreenCell>>,
+    alternate: bool,
+    x: usize,
+    y: usize,
+    /// Cursor is at the last column and the next character will wrap.
+    wrap_pending: bool,
+    style: TextStyle,
+    saved: (usize, usize, TextStyle),
+    /// First line of the scroll region.
+    top: usize,
+    /// Last line of the scroll region (inclusive).
+    bottom: usize,
+    autowrap: bool,
+    cursor_visible: bool,
+    /// Position of the last printed character.
+    last: Option<(usize, usize)>,
+    /// Incomplete sequence or character.
+    pending: Vec<u8>,
+}
+
+impl Screen {
+    /// Create empty screen with the given size.
+    pub fn new(width: usize, height: usize) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+        Self {
+            width,
+            height,
+            lines: vec![vec![ScreenCell::default(); width]; height],
+            other: vec![vec![ScreenCell::default(); width]; height],
+            alternate: false,
+            x: 0,
+            y: 0,
+            wrap_pending: false,
+            style: TextStyle::default(),
+            saved: (0, 0, TextStyle::default()),
+            top: 0,
+            bottom: height - 1,
+            autowrap: true,
+            cursor_visible: true,
+            last: None,
+            pending: vec![],
+        }
+    }
+
+    /// Get the width of the screen in cells.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of the screen in cells.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the position of the cursor as `(column, row)`.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    /// Checks whether the cursor is visible.
+    pub fn is_cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Checks whether the alternate buffer is active.
+    pub fn is_alternate(&self) -> bool {
+        self.alternate
+    }
+
+    /// Get the current style that is used for printed characters.
+    pub fn style(&self) -> TextStyle {
+        self.style
+    }
+
+    /// Get the cell at the given position.
+    pub fn cell(&self, x: usize, y: usize) -> &ScreenCell {
+        &self.lines[y][x]
+    }
+
+    /// Get the cells of the given row.
+    pub fn row(&self, y: usize) -> &[ScreenCell] {
+        &self.lines[y]
+    }
+
+    /// Get the text of the given row without the trailing spaces.
+    pub fn line(&self, y: usize) -> String {
+        let res: String =
+            self.lines[y].iter().map(|c| c.text.as_str()).collect();
+        res.trim_end().to_string()
+    }
+
+    /// Get the text of the given row with SGR sequences. It doesn't have
+    /// trailing spaces with default style and it ends with reset if the
+    /// style is not the default style.
+    pub fn styled_line(&self, y: usize) -> String {
+        let row = &self.lines[y];
+        let len = row
+            .iter()
+            .rposition(|c| c.text != " " || !c.style.is_default())
+            .map_or(0, |p| p + 1);
+
+        let mut res = String::new();
+        let mut style = TextStyle::default();
+        for c in &row[..len] {
+            if c.style != style {
+                if !style.is_default() {
+                    res += codes::RESET;
+                }
+                res += &c.style.to_sgr();
+                style = c.style;
+            }
+            res += &c.text;
+        }
+        if !style.is_default() {
+            res += codes::RESET;
+        }
+        res
+    }
+
+    /// Get the text of the screen. Lines are separated with `\n`. Trailing
+    /// spaces and trailing empty lines are removed.
+    pub fn text(&self) -> String {
+        join_lines((0..self.height).map(|y| self.line(y)))
+    }
+
+    /// Get the text of the screen with SGR sequences. See
+    /// [`Screen::styled_line`] and [`Screen::text`].
+    pub fn styled_text(&self) -> String {
+        join_lines((0..self.height).map(|y| self.styled_line(y)))
+    }
+
+    /// Assert that the text of the screen is the expected text. Trailing
+    /// spaces, leading newline and trailing empty lines in `expected` are
+    /// ignored, so that it may be written as raw string.
+    ///
+    /// # Panics
+    /// If the text doesn't match. The message contains both the screen and
+    /// the expected text.
+    #[track_caller]
+    pub fn assert_text(&self, expected: &str) {
+        let expected = expected.strip_prefix('\n').unwrap_or(expected);
+        let expected =
+            join_lines(expected.lines().map(|l| l.trim_end().to_string()));
+        let text = self.text();
+        if text != expected {
+            let border = "-".repeat(self.width);
+            panic!(
+                "screen doesn't match.\nscreen:\n{border}\n{text}\n\
+                {border}\nexpected:\n{border}\n{expected}\n{border}"
+            );
+        }
+    }
+
+    /// Resize the screen. Content that doesn't fit is cut off.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        let width = width.max(1);
+        let height = height.max(1);
+        for lines in [&mut self.lines, &mut self.other] {
+            lines.resize(height, vec![]);
+            for l in lines {
+                l.resize(width, ScreenCell::default());
+            }
+        }
+        self.width = width;
+        self.height = height;
+        self.top = 0;
+        self.bottom = height - 1;
+        self.x = self.x.min(width - 1);
+        self.y = self.y.min(height - 1);
+        self.wrap_pending = false;
+        self.last = None;
+    }
+
+    /// Interpret the given output.
+    pub fn feed(&mut self, data: &[u8]) {
+        let mut data = if self.pending.is_empty() {
+            data.to_vec()
+        } else {
+            let mut d = mem::take(&mut self.pending);
+            d.extend_from_slice(data);
+            d
+        };
+
+        let mut pos = 0;
+        while pos < data.len() {
+            match self.parse(&data[pos..]) {
+                Some(len) => pos += len,
+                None => {
+                    self.pending = data.split_off(pos);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Interpret single character or sequence at the start of `data`.
+    /// Returns the number of consumed bytes or `None` if the data is
+    /// incomplete.
+    fn parse(&mut self, data: &[u8]) -> Option<usize> {
+        match data[0] {
+            0x1b => self.parse_escape(data),
+            b'\r' => {
+                self.x = 0;
+                self.wrap_pending = false;
+                Some(1)
+            }
+            b'\n' | 0xb | 0xc => {
+                self.linefeed();
+                Some(1)
+            }
+            8 => {
+                self.x = self.x.saturating_sub(1);
+                self.wrap_pending = false;
+                Some(1)
+            }
+            b'\t' => {
+                self.x =
+                    ((self.x / TAB_WIDTH + 1) * TAB_WIDTH).min(self.width - 1);
+                self.wrap_pending = false;
+                Some(1)
+            }
+            ..0x20 | 0x7f => Some(1),
+            b => {
+                let len = match b {
+                    ..0x80 => 1,
+                    0xc0..0xe0 => 2,
+                    0xe0..0xf0 => 3,
+                    0xf0.. => 4,
+                    _ => {
+                        self.print(char::REPLACEMENT_CHARACTER);
+                        return Some(1);
+                    }
+                };
+                if data.len() < len {
+                    return None;
+                }
+                match std::str::from_utf8(&data[..len]) {
+                    Ok(s) => {
+                        s.chars().for_each(|c| self.print(c));
+                        Some(len)
+                    }
+                    Err(_) => {
+                        self.print(char::REPLACEMENT_CHARACTER);
+                        Some(1)
+                    }
+                }
+            }
+        }
+    }
+
+    fn parse_escape(&mut self, data: &[u8]) -> Option<usize> {
+        let c = *data.get(1)?;
+        match c {
+            b'[' => {
+                let end =
+                    data[2..].iter().position(|b| (0x40..0x7f).contains(b))?
+                        + 2;
+                let args = String::from_utf8_lossy(&data[2..end]);
+                self.csi(&args, data[end]);
+                Some(end + 1)
+            }
+            // OSC | DCS | PM | APC
+            b']' | b'P' | b'^' | b'_' => {
+                let end = data.windows(2).position(|w| w == b"\x1b\\");
+                let bel = (c == b']')
+                    .then(|| data.iter().position(|b| *b == 7))
+                    .flatten();
+                match (end, bel) {
+                    (Some(e), Some(b)) if b < e => Some(b + 1),
+                    (Some(e), _) => Some(e + 2),
+                    (None, Some(b)) => Some(b + 1),
+                    (None, None) => None,
+                }
+            }
+            b'(' | b')' | b'*' | b'+' | b'#' => (data.len() >= 3).then_some(3),
+            b'7' => {
+                self.save_cursor();
+                Some(2)
+            }
+            b'8' => {
+                self.restore_cursor();
+                Some(2)
+            }
+            b'D' => {
+                self.linefeed();
+                Some(2)
+            }
+            b'E' => {
+                self.x = 0;
+                self.linefeed();
+                Some(2)
+            }
+            b'M' => {
+                self.reverse_index();
+                Some(2)
+            }
+            b'c' => {
+                *self = Self::new(self.width, self.height);
+                Some(2)
+            }
+            _ => Some(2),
+        }
+    }
+
+    fn csi(&mut self, args: &str, f: u8) {
+        let private = args.starts_with(['?', '>', '<', '=']);
+        let nums: Vec<usize> = args
+            .trim_start_matches(['?', '>', '<', '='])
+            .split(';')
+            .map(|a| a.parse().unwrap_or_default())
+            .collect();
+        // Argument with default value 1.
+        let arg = |i: usize| nums.get(i).copied().filter(|a| *a != 0);
+        let n = arg(0).unwrap_or(1);
+
+        if private {
+            match f {
+                b'h' | b'l' => self.set_modes(&nums, f == b'h'),
+                _ => {}
+            }
+            return;
+        }
+
+        self.wrap_pending &= matches!(f, b'm');
+        match f {
+            b'A' => self.y = self.y.saturating_sub(n),
+            b'B' => self.y = self.y.saturating_add(n).min(self.height - 1),
+            b'C' => self.x = self.x.saturating_add(n).min(self.width - 1),
+            b'D' => self.x = self.x.saturating_sub(n),
+            b'E' => {
+                self.y = self.y.saturating_add(n).min(self.height - 1);
+                self.x = 0;
+            }
+            b'F' => {
+                self.y = self.y.saturating_sub(n);
+                self.x = 0;
+            }
+            b'G' | b'`' => self.x = (n - 1).min(self.width - 1),
+            b'd' => self.y = (n - 1).min(self.height - 1),
+            b'H' | b'f' => {
+                self.y = (n - 1).min(self.height - 1);
+                self.x = (arg(1).unwrap_or(1) - 1).min(self.width - 1);
+            }
+            b'J' => match nums[0] {
+                0 => {
+                    self.erase(self.y, self.x..self.width);
+                    for y in self.y + 1..self.height {
+                        self.erase(y, 0..self.width);
+                    }
+                }
+                1 => {
+                    for y in 0..self.y {
+                        self.erase(y, 0..self.width);
+                    }
+                    self.erase(self.y, 0..self.x + 1);
+                }
+                _ => {
+                    for y in 0..self.height {
+                        self.erase(y, 0..self.width);
+                    }
+                }
+            },
+            b'K' => match nums[0] {
+                0 => self.erase(self.y, self.x..self.width),
+                1 => self.erase(self.y, 0..self.x + 1),
+                _ => self.erase(self.y, 0..self.width),
+            },
+            b'X' => self.erase(self.y, self.x..self.x.saturating_add(n)),
+            b'@' => {
+                let blank = self.blank();
+                let line = &mut self.lines[self.y];
+                for _ in 0..n.min(self.width - self.x) {
+                    line.insert(self.x, blank.clone());
+                }
+                line.truncate(self.width);
+            }
+            b'P' => {
+                let blank = self.blank();
+                let line = &mut self.lines[self.y];
+                for _ in 0..n.min(self.width - self.x) {
+                    line.remove(self.x);
+                    line.push(blank.clone());
+                }
+            }
+            b'L' if (self.top..=self.bottom).contains(&self.y) => {
+                self.scroll_down_from(self.y, n);
+                self.x = 0;
+            }
+            b'M' if (self.top..=self.bottom).contains(&self.y) => {
+                self.scroll_up_from(self.y, n);
+                self.x = 0;
+            }
+            b'S' => self.scroll_up_from(self.top, n),
+            b'T' => self.scroll_down_from(self.top, n),
+            b'm' => self.style.apply_args(args),
+            b'r' => {
+                let top = n - 1;
+                let bottom =
+                    arg(1).unwrap_or(self.height).min(self.height) - 1;
+                if top < bottom {
+                    self.top = top;
+                    self.bottom = bottom;
+                    self.x = 0;
+                    self.y = 0;
+                }
+            }
+            b's' => self.save_cursor(),
+            b'u' => self.restore_cursor(),
+            _ => {}
+        }
+    }
+
+    fn set_modes(&mut self, modes: &[usize], enable: bool) {
+        for m in modes {
+            match m {
+                7 => self.autowrap = enable,
+                25 => self.cursor_visible = enable,
+                47 | 1047 => self.set_alternate(enable),
+                1049 => {
+                    if enable {
+                        self.save_cursor();
+                        self.set_alternate(true);
+                    } else {
+                        self.set_alternate(false);
+                        self.restore_cursor();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn set_alternate(&mut self, enable: bool) {
+        if self.alternate == enable {
+            return;
+        }
+        self.alternate = enable;
+        mem::swap(&mut self.lines, &mut self.other);
+        if enable {
+            for l in &mut self.lines {
+                l.fill(ScreenCell::default());
+            }
+        }
+        self.last = None;
+    }
+
+    fn print(&mut self, c: char) {
+        let w = c.width().unwrap_or_default();
+        let joined = self
+            .last
+            .is_some_and(|(x, y)| self.lines[y][x].text.ends_with(ZWJ));
+        if w == 0 || joined {
+            // Part of the previous grapheme cluster.
+            if let Some((x, y)) = self.last {
+                self.lines[y][x].text.push(c);
+            }
+            return;
+        }
+
+        if self.wrap_pending || self.x + w > self.width {
+            if self.autowrap {
+                self.x = 0;
+                self.linefeed();
+            } else {
+                self.x = self.width.saturating_sub(w);
+            }
+        }
+        self.wrap_pending = false;
+
+        let line = &mut self.lines[self.y];
+        // Don't leave halves of wide characters.
+        if line[self.x].is_continuation() && self.x > 0 {
+            line[self.x - 1] = ScreenCell::default();
+        }
+        let end = self.x + w;
+        if end < self.width && line[end].is_continuation() {
+            line[end] = ScreenCell::default();
+        }
+
+        line[self.x] = ScreenCell {
+            text: c.to_string(),
+            style: self.style,
+        };
+        for cell in line.iter_mut().take(end).skip(self.x + 1) {
+            *cell = ScreenCell {
+                text: String::new(),
+                style: self.style,
+            };
+        }
+        self.last = Some((self.x, self.y));
+
+        if end >= self.width {
+            self.x = self.width - 1;
+            self.wrap_pending = self.autowrap;
+        } else {
+            self.x = end;
+        }
+    }
+
+    fn linefeed(&mut self) {
+        self.wrap_pending = false;
+        if self.y == self.bottom {
+            self.scroll_up_from(self.top, 1);
+        } else if self.y + 1 < self.height {
+            self.y += 1;
+        }
+    }
+
+    fn reverse_index(&mut self) {
+        self.wrap_pending = false;
+        if self.y == self.top {
+            self.scroll_down_from(self.top, 1);
+        } else {
+            self.y = self.y.saturating_sub(1);
+        }
+    }
+
+    /// Move lines from `y` to the bottom of the scroll region up by `n`.
+    fn scroll_up_from(&mut self, y: usize, n: usize) {
+        let blank = vec![self.blank(); self.width];
+        for _ in 0..n.min(self.bottom + 1 - y) {
+            self.lines.remove(y);
+            self.lines.insert(self.bottom, blank.clone());
+        }
+        self.last = None;
+    }
+
+    /// Move lines from `y` to the bottom of the scroll region down by `n`.
+    fn scroll_down_from(&mut self, y: usize, n: usize) {
+        let blank = vec![self.blank(); self.width];
+        for _ in 0..n.min(self.bottom + 1 - y) {
+            self.lines.remove(self.bottom);
+            self.lines.insert(y, blank.clone());
+        }
+        self.last = None;
+    }
+
+    fn erase(&mut self, y: usize, range: std::ops::Range<usize>) {
+        let blank = self.blank();
+        let start = range.start.min(self.width);
+        let end = range.end.min(self.width);
+        let line = &mut self.lines[y];
+        // Don't leave halves of wide characters.
+        if start > 0 && start < end && line[start].is_continuation() {
+            line[start - 1] = blank.clone();
+        }
+        if end < self.width && line[end].is_continuation() {
+            line[end] = blank.clone();
+        }
+        line[start..end].fill(blank);
+    }
+
+    fn save_cursor(&mut self) {
+        self.saved = (self.x, self.y, self.style);
+    }
+
+    fn restore_cursor(&mut self) {
+        (self.x, self.y, self.style) = self.saved;
+        self.x = self.x.min(self.width - 1);
+        self.y = self.y.min(self.height - 1);
+        self.wrap_pending = false;
+    }
+
+    /// Erased cell. Only the background color of the current style is kept.
+    fn blank(&self) -> ScreenCell {
+        ScreenCell::blank(TextStyle {
+            bg: self.style.bg,
+            ..Default::default()
+        })
+    }
+}
+
+impl Write for Screen {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.feed(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn join_lines(lines: impl Iterator<Item = String>) -> String {
+    let mut lines: Vec<_> = lines.collect();
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
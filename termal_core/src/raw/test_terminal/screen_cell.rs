@@ -0,0 +1,35 @@
+use crate::term_text::TextStyle;
+
+/// Single cell of [`Screen`].
+///
+/// [`Screen`]: super::Screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenCell {
+    /// Grapheme cluster in the cell. It is empty if the cell is covered by
+    /// wide character in the previous cell.
+    pub text: String,
+    /// Style of the cell.
+    pub style: TextStyle,
+}
+
+impl ScreenCell {
+    /// Create empty cell with the given style.
+    pub fn blank(style: TextStyle) -> Self {
+        Self {
+            text: " ".into(),
+            style,
+        }
+    }
+
+    /// Checks whether this cell is covered by wide character in the previous
+    /// cell.
+    pub fn is_continuation(&self) -> bool {
+        self.text.is_empty()
+    }
+}
+
+impl Default for ScreenCell {
+    fn default() -> Self {
+        Self::blank(TextStyle::default())
+    }
+}
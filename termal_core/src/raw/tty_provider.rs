@@ -0,0 +1,168 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Read},
+    os::fd::AsRawFd,
+    time::Duration,
+};
+
+use crate::error::Result;
+
+use super::{
+    IoProvider, ValueOrMut, WaitForIn, WakeSignal, is_raw_mode_enabled,
+    sys::unix,
+};
+
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+
+#[cfg(feature = "async")]
+use super::{AsyncWaitForIn, InputWatch};
+
+/// IoProvider that reads from and writes to the controlling terminal
+/// (`/dev/tty`). It may be used when stdin or stdout is redirected.
+#[derive(Debug)]
+pub struct TtyProvider {
+    out: File,
+    input: TtyIn,
+}
+
+/// Buffered input from the controlling terminal. It is the input of
+/// [`TtyProvider`].
+#[derive(Debug)]
+pub struct TtyIn {
+    input: BufReader<File>,
+    #[cfg(feature = "async")]
+    watch: InputWatch,
+}
+
+impl TtyProvider {
+    /// Open the controlling terminal.
+    pub fn open() -> Result<Self> {
+        let out =
+            OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+        let input = TtyIn {
+            input: BufReader::new(out.try_clone()?),
+            #[cfg(feature = "async")]
+            watch: InputWatch::new(),
+        };
+        Ok(Self { out, input })
+    }
+}
+
+impl WaitForIn for TtyProvider {
+    fn wait_for_in(&self, timeout: Duration) -> Result<bool> {
+        self.input.wait_for_in(timeout)
+    }
+
+    fn wait_for_in_or(
+        &self,
+        signal: &WakeSignal,
+        timeout: Duration,
+    ) -> Result<bool> {
+        self.input.wait_for_in_or(signal, timeout)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncWaitForIn for TtyProvider {
+    fn poll_for_in(&self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.input.poll_for_in(cx)
+    }
+
+    fn cancel_poll(&self) {
+        self.input.cancel_poll();
+    }
+}
+
+impl WaitForIn for TtyIn {
+    fn wait_for_in(&self, timeout: Duration) -> Result<bool> {
+        if !self.input.buffer().is_empty() {
+            return Ok(true);
+        }
+        unix::wait_for_fd(self.input.get_ref().as_raw_fd(), timeout)
+    }
+
+    fn wait_for_in_or(
+        &self,
+        signal: &WakeSignal,
+        timeout: Duration,
+    ) -> Result<bool> {
+        if signal.is_set() {
+            return Ok(false);
+        }
+        if !self.input.buffer().is_empty() {
+            return Ok(true);
+        }
+        unix::wait_for_fd_or_fd(
+            self.input.get_ref().as_raw_fd(),
+            signal.as_raw_fd(),
+            timeout,
+        )
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncWaitForIn for TtyIn {
+    fn poll_for_in(&self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if self.wait_for_in(Duration::ZERO)? {
+            return Poll::Ready(Ok(()));
+        }
+
+        self.watch.poll(cx, || {
+            // The thread has its own handle, so it doesn't outlive the file.
+            let tty = self.input.get_ref().try_clone()?;
+            Ok(move |s: &WakeSignal| {
+                unix::wait_for_fd_or_fd(
+                    tty.as_raw_fd(),
+                    s.as_raw_fd(),
+                    Duration::MAX,
+                )
+            })
+        })
+    }
+
+    fn cancel_poll(&self) {
+        self.watch.cancel();
+    }
+}
+
+impl Read for TtyIn {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+impl BufRead for TtyIn {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.input.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.input.consume(amt)
+    }
+}
+
+impl IoProvider for TtyProvider {
+    type Out = File;
+    type In = TtyIn;
+
+    fn get_out(&mut self) -> ValueOrMut<'_, Self::Out> {
+        ValueOrMut::Mut(&mut self.out)
+    }
+
+    fn get_in(&mut self) -> ValueOrMut<'_, Self::In> {
+        ValueOrMut::Mut(&mut self.input)
+    }
+
+    fn is_out_terminal(&self) -> bool {
+        true
+    }
+
+    fn is_in_terminal(&self) -> bool {
+        true
+    }
+
+    fn is_out_raw(&self) -> bool {
+        is_raw_mode_enabled()
+    }
+}
@@ -5,21 +5,48 @@ use std::{
 
 use crate::error::Result;
 
-use super::wait_for_stdin;
+use super::{WakeSignal, wait_for_stdin, wait_for_stdin_or, wake_signal};
 
 /// The type can wait for input with the given timeout.
 pub trait WaitForIn {
     fn wait_for_in(&self, timeout: Duration) -> Result<bool>;
+
+    /// Wait for input with the given timeout. The waiting may be interrupted
+    /// with the signal. By default this waits in short slices and checks the
+    /// signal between them.
+    fn wait_for_in_or(
+        &self,
+        signal: &WakeSignal,
+        timeout: Duration,
+    ) -> Result<bool> {
+        wake_signal::wait_sliced(signal, timeout, |t| self.wait_for_in(t))
+    }
 }
 
 impl WaitForIn for Stdin {
     fn wait_for_in(&self, timeout: Duration) -> Result<bool> {
         wait_for_stdin(timeout)
     }
+
+    fn wait_for_in_or(
+        &self,
+        signal: &WakeSignal,
+        timeout: Duration,
+    ) -> Result<bool> {
+        wait_for_stdin_or(signal, timeout)
+    }
 }
 
 impl WaitForIn for StdinLock<'static> {
     fn wait_for_in(&self, timeout: Duration) -> Result<bool> {
         wait_for_stdin(timeout)
     }
+
+    fn wait_for_in_or(
+        &self,
+        signal: &WakeSignal,
+        timeout: Duration,
+    ) -> Result<bool> {
+        wait_for_stdin_or(signal, timeout)
+    }
 }
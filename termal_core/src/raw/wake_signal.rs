@@ -0,0 +1,80 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::error::Result;
+
+#[cfg(unix)]
+use std::os::fd::{AsRawFd, OwnedFd, RawFd};
+
+#[cfg(unix)]
+use super::sys::unix;
+
+/// Maximum duration of single wait when the waiting itself cannot be
+/// interrupted by [`WakeSignal`].
+const WAIT_SLICE: Duration = Duration::from_millis(50);
+
+/// One shot signal that interrupts waiting for input. On unix it is
+/// implemented with self pipe so the waiting is interrupted immidietely.
+#[derive(Debug)]
+pub struct WakeSignal {
+    set: AtomicBool,
+    #[cfg(unix)]
+    pipe: (OwnedFd, OwnedFd),
+}
+
+impl WakeSignal {
+    /// Create new signal that is not set.
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            set: AtomicBool::new(false),
+            #[cfg(unix)]
+            pipe: unix::pipe()?,
+        })
+    }
+
+    /// Set the signal and interrupt all waiting on it.
+    pub fn wake(&self) {
+        if self.set.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        #[cfg(unix)]
+        {
+            _ = unix::write_byte(self.pipe.1.as_raw_fd(), 0);
+        }
+    }
+
+    /// Checks whether the signal has been set.
+    pub fn is_set(&self) -> bool {
+        self.set.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for WakeSignal {
+    /// Get file descriptor that becomes readable once the signal is set.
+    fn as_raw_fd(&self) -> RawFd {
+        self.pipe.0.as_raw_fd()
+    }
+}
+
+/// Wait with the given function in short slices so that the signal may
+/// interrupt the waiting.
+pub(crate) fn wait_sliced(
+    signal: &WakeSignal,
+    timeout: Duration,
+    wait: impl Fn(Duration) -> Result<bool>,
+) -> Result<bool> {
+    let start = Instant::now();
+    while !signal.is_set() {
+        let remaining = timeout.saturating_sub(start.elapsed());
+        if wait(remaining.min(WAIT_SLICE))? {
+            return Ok(true);
+        }
+        if remaining <= WAIT_SLICE {
+            break;
+        }
+    }
+    Ok(false)
+}
@@ -0,0 +1,12 @@
+/// Alignment of text within the given width.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Align {
+    /// The text is on the left and padding on the right.
+    #[default]
+    Left,
+    /// The text is on the right and padding on the left.
+    Right,
+    /// The text is in the center. If the padding cannot be split evenly,
+    /// there is more padding on the right.
+    Center,
+}
@@ -0,0 +1,373 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::codes;
+
+use super::{Color, Palette, StyledSpans, TextStyle, Underline, palette::hex};
+
+/// Width of character in SVG.
+const CHAR_WIDTH: usize = 6;
+/// Height of line in SVG.
+const LINE_HEIGHT: usize = 12;
+/// Offset of the text baseline from the top of the line in SVG.
+const BASELINE: usize = 9;
+/// Padding around the text in SVG.
+const PADDING: usize = 6;
+
+/// Convert the text to HTML. If `palette` is `None`, classes are used
+/// instead of inline styles where possible.
+pub(super) fn to_html(text: &str, palette: Option<&Palette>) -> String {
+    let mut res = "<pre class=\"tm\"".to_string();
+    if let Some(p) = palette {
+        res += &format!(
+            " style=\"color:{};background-color:{}\"",
+            hex(p.fg),
+            hex(p.bg)
+        );
+    }
+    res.push('>');
+
+    for (i, line) in lines(text).iter().enumerate() {
+        if i != 0 {
+            res.push('\n');
+        }
+        for (s, style) in line {
+            let (classes, css) = html_style(style, palette);
+            if classes.is_empty() && css.is_empty() {
+                escape(&mut res, s);
+                continue;
+            }
+            res += "<span";
+            if !classes.is_empty() {
+                res += &format!(" class=\"{}\"", classes.join(" "));
+            }
+            if !css.is_empty() {
+                res += &format!(" style=\"{css}\"");
+            }
+            res.push('>');
+            escape(&mut res, s);
+            res += "</span>";
+        }
+    }
+
+    res += "</pre>";
+    res
+}
+
+/// Convert the text to SVG image of terminal.
+pub(super) fn to_svg(text: &str, palette: &Palette) -> String {
+    let lines = lines(text);
+    let cols = lines
+        .iter()
+        .map(|l| l.iter().map(|(s, _)| s.width()).sum::<usize>())
+        .max()
+        .unwrap_or_default();
+    let width = cols * CHAR_WIDTH + 2 * PADDING;
+    let height = lines.len() * LINE_HEIGHT + 2 * PADDING;
+
+    let mut res = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" \
+        height=\"{height}\" font-family=\"monospace\" font-size=\"10\">\n\
+        <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        hex(palette.bg)
+    );
+
+    for (i, line) in lines.iter().enumerate() {
+        let y = PADDING + i * LINE_HEIGHT;
+        let mut col = 0;
+        for (s, style) in line {
+            let x = PADDING + col * CHAR_WIDTH;
+            let w = s.width();
+            col += w;
+
+            let (mut fg, mut bg) = (style.fg, style.bg);
+            if style.inverse {
+                (fg, bg) = (
+                    Some(Color::Rgb(palette.bg(bg))),
+                    Some(Color::Rgb(palette.fg(fg))),
+                );
+            }
+
+            if let Some(bg) = bg {
+                res += &format!(
+                    "<rect x=\"{x}\" y=\"{y}\" width=\"{}\" \
+                    height=\"{LINE_HEIGHT}\" fill=\"{}\"/>\n",
+                    w * CHAR_WIDTH,
+                    hex(palette.rgb(bg))
+                );
+            }
+
+            if style.invisible || s.trim().is_empty() {
+                continue;
+            }
+
+            res += &format!(
+                "<text x=\"{x}\" y=\"{}\" fill=\"{}\"",
+                y + BASELINE,
+                hex(palette.fg(fg))
+            );
+            if style.bold {
+                res += " font-weight=\"bold\"";
+            }
+            if style.italic {
+                res += " font-style=\"italic\"";
+            }
+            if style.faint {
+                res += " opacity=\"0.5\"";
+            }
+            let decoration = decoration(style);
+            if !decoration.is_empty() {
+                res += &format!(" text-decoration=\"{decoration}\"");
+            }
+            res += " xml:space=\"preserve\">";
+            escape(&mut res, s);
+            res += "</text>\n";
+        }
+    }
+
+    res += "</svg>\n";
+    res
+}
+
+/// Split the text into lines of runs with the same style. Newline, tab,
+/// carriage return, cursor movement (`CSI A`, `B`, `C`, `D`, `E`, `F`, `G`,
+/// `H`, `d` and `f`) and erase in line (`CSI K`) are interpreted, so the
+/// following text overwrites the cells. Absolute positions are relative to
+/// the start of the text. Other control sequences than SGR are ignored.
+fn lines(text: &str) -> Vec<Vec<(String, TextStyle)>> {
+    // Cells of the lines. Continuation of wide char has empty text.
+    let mut lines: Vec<Vec<(String, TextStyle)>> = vec![vec![]];
+    let (mut row, mut col) = (0, 0);
+
+    for (span, style) in StyledSpans::new(text, TextStyle::default()) {
+        if lines.len() <= row {
+            lines.resize_with(row + 1, Vec::new);
+        }
+        let line = &mut lines[row];
+        match span.text() {
+            "\n" => {
+                row += 1;
+                col = 0;
+                if lines.len() <= row {
+                    lines.resize_with(row + 1, Vec::new);
+                }
+            }
+            "\r" => col = 0,
+            "\t" => {
+                for _ in 0..8 - col % 8 {
+                    put(line, &mut col, " ", style);
+                }
+            }
+            s if span.is_control() => {
+                let Some(seq) = s.strip_prefix(codes::CSI) else {
+                    continue;
+                };
+                let Some(cmd) = seq.chars().last() else {
+                    continue;
+                };
+                let args = &seq[..seq.len() - cmd.len_utf8()];
+                if !args.chars().all(|c| c.is_ascii_digit() || c == ';') {
+                    continue;
+                }
+                let mut args = args.split(';').map(|a| a.parse().ok());
+                let mut arg = || args.next().flatten().unwrap_or(0_usize);
+                // Movement by zero moves by one.
+                let n = |a: usize| a.max(1);
+                let blank = || (" ".to_string(), TextStyle::default());
+                match cmd {
+                    'A' => row = row.saturating_sub(n(arg())),
+                    'B' => row = row.saturating_add(n(arg())),
+                    'C' => col = col.saturating_add(n(arg())),
+                    'D' => col = col.saturating_sub(n(arg())),
+                    'E' => (row, col) = (row.saturating_add(n(arg())), 0),
+                    'F' => (row, col) = (row.saturating_sub(n(arg())), 0),
+                    'G' => col = n(arg()) - 1,
+                    'd' => row = n(arg()) - 1,
+                    'H' | 'f' => (row, col) = (n(arg()) - 1, n(arg()) - 1),
+                    'K' => match arg() {
+                        0 => line.truncate(col),
+                        1 => {
+                            let end = (col + 1).min(line.len());
+                            line[..end].fill_with(blank);
+                        }
+                        2 => line.clear(),
+                        _ => {}
+                    },
+                    _ => {}
+                }
+            }
+            s => {
+                for g in s.graphemes(true) {
+                    put(line, &mut col, g, style);
+                }
+            }
+        }
+    }
+
+    if lines.len() > 1 && lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.into_iter().map(runs).collect()
+}
+
+/// Write the grapheme to the line cells at the column `col` and move the
+/// column after it.
+fn put(
+    line: &mut Vec<(String, TextStyle)>,
+    col: &mut usize,
+    g: &str,
+    style: TextStyle,
+) {
+    let blank = || (" ".to_string(), TextStyle::default());
+    let w = g.width();
+    if w == 0 {
+        // Part of the previous grapheme cluster.
+        let end = (*col).min(line.len());
+        let prev = line[..end].iter_mut().rfind(|(t, _)| !t.is_empty());
+        if let Some((t, _)) = prev {
+            t.push_str(g);
+        }
+        return;
+    }
+
+    if line.len() < *col + w {
+        line.resize_with(*col + w, blank);
+    }
+    // Don't leave halves of wide characters.
+    if line[*col].0.is_empty() && *col > 0 {
+        line[*col - 1] = blank();
+    }
+    if line.get(*col + w).is_some_and(|(t, _)| t.is_empty()) {
+        line[*col + w] = blank();
+    }
+
+    line[*col] = (g.to_string(), style);
+    for c in &mut line[*col + 1..*col + w] {
+        *c = (String::new(), style);
+    }
+    *col += w;
+}
+
+/// Join the cells of line into runs with the same style.
+fn runs(cells: Vec<(String, TextStyle)>) -> Vec<(String, TextStyle)> {
+    let mut res: Vec<(String, TextStyle)> = vec![];
+    for (s, style) in cells {
+        match res.last_mut() {
+            Some((t, st)) if *st == style => t.push_str(&s),
+            _ => res.push((s, style)),
+        }
+    }
+    res
+}
+
+/// Get the classes and inline CSS for the style. Classes are used only if
+/// `palette` is `None`.
+fn html_style(
+    style: &TextStyle,
+    palette: Option<&Palette>,
+) -> (Vec<String>, String) {
+    let mut classes = vec![];
+    let mut css = String::new();
+
+    let (mut fg, mut bg) = (style.fg, style.bg);
+    if style.inverse {
+        if let Some(p) = palette {
+            (fg, bg) =
+                (Some(Color::Rgb(p.bg(bg))), Some(Color::Rgb(p.fg(fg))));
+        } else {
+            (fg, bg) = (bg, fg);
+            if fg.is_none() {
+                classes.push("tm-inverse-fg".to_string());
+            }
+            if bg.is_none() {
+                classes.push("tm-inverse-bg".to_string());
+            }
+        }
+    }
+
+    let palette_rgb = palette.copied().unwrap_or_default();
+    for (color, kind, prop) in
+        [(fg, "fg", "color"), (bg, "bg", "background-color")]
+    {
+        match color {
+            None => {}
+            Some(Color::Basic(c) | Color::Indexed(c))
+                if palette.is_none() && c < 16 =>
+            {
+                classes.push(format!("tm-{kind}-{c}"));
+            }
+            Some(c) => {
+                css += &format!("{prop}:{};", hex(palette_rgb.rgb(c)));
+            }
+        }
+    }
+
+    for (set, class, prop) in [
+        (style.bold, "tm-bold", "font-weight:bold;"),
+        (style.faint, "tm-faint", "opacity:0.5;"),
+        (style.italic, "tm-italic", "font-style:italic;"),
+        (style.invisible, "tm-invisible", "visibility:hidden;"),
+    ] {
+        if !set {
+            continue;
+        }
+        if palette.is_some() {
+            css += prop;
+        } else {
+            classes.push(class.to_string());
+        }
+    }
+
+    let decoration = decoration(style);
+    if !decoration.is_empty() {
+        css += &format!("text-decoration:{decoration};");
+        let line_style = match style.underline {
+            Underline::Double => "double",
+            Underline::Curly => "wavy",
+            Underline::Dotted => "dotted",
+            Underline::Dashed => "dashed",
+            _ => "",
+        };
+        if !line_style.is_empty() {
+            css += &format!("text-decoration-style:{line_style};");
+        }
+        if let Some(c) = style.underline_color {
+            if style.underline != Underline::None {
+                css += &format!(
+                    "text-decoration-color:{};",
+                    hex(palette_rgb.rgb(c))
+                );
+            }
+        }
+    }
+
+    (classes, css)
+}
+
+/// Get the value of CSS `text-decoration-line` for the style.
+fn decoration(style: &TextStyle) -> String {
+    let mut res = vec![];
+    if style.underline != Underline::None {
+        res.push("underline");
+    }
+    if style.striketrough {
+        res.push("line-through");
+    }
+    if style.overline {
+        res.push("overline");
+    }
+    res.join(" ")
+}
+
+/// Append the text escaped for HTML and XML.
+fn escape(res: &mut String, s: &str) {
+    for c in s.chars() {
+        match c {
+            '&' => *res += "&amp;",
+            '<' => *res += "&lt;",
+            '>' => *res += "&gt;",
+            '"' => *res += "&quot;",
+            c => res.push(c),
+        }
+    }
+}
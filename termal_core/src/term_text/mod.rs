@@ -1,10 +1,29 @@
-use std::{borrow::Cow, cell::Cell, fmt::Display};
-
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    fmt::Display,
+    ops::{Bound, RangeBounds},
+};
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::codes;
+
+mod align;
+mod export;
+mod palette;
+mod styled_spans;
 mod term_text_metadata;
 mod term_text_span;
 mod term_text_spans;
+mod text_style;
+mod wrap;
 
-pub use self::{term_text_metadata::*, term_text_span::*, term_text_spans::*};
+pub use self::{
+    align::*, palette::*, styled_spans::*, term_text_metadata::*,
+    term_text_span::*, term_text_spans::*, text_style::*,
+};
 
 /// String with control escape sequences.
 ///
@@ -95,6 +114,12 @@ impl<'a> TermText<'a> {
         self.get_metadata().display_chars()
     }
 
+    /// Get the number of terminal columns taken by the text when it is
+    /// displayed. If it is not cached it will be calculated.
+    pub fn display_width(&self) -> usize {
+        self.get_metadata().width
+    }
+
     /// Get the number of display bytes. If it is not cached it will be
     /// calculated.
     pub fn display_bytes_cnt(&self) -> usize {
@@ -120,28 +145,23 @@ impl<'a> TermText<'a> {
         TermTextSpans::new(&self.text)
     }
 
+    /// Get iterator over the spans together with the style of each span. The
+    /// style is interpreted from the SGR sequences in the text starting with
+    /// the default style.
+    pub fn styled_spans(&self) -> StyledSpans<'_> {
+        StyledSpans::new(&self.text, TextStyle::default())
+    }
+
     /// Strips the string of control sequences
     #[inline]
     pub fn strip_control(&self) -> String {
         let mut res = String::new();
         if let Some(meta) = self.metadata.get() {
             res.reserve_exact(self.text.len() - meta.control_bytes);
-            for span in self.spans().filter(|s| !s.is_control()) {
-                res.push_str(span.text());
-            }
-        } else {
-            let mut meta = TermTextMetadata::default();
-
-            for span in self.spans().filter(|s| !s.is_control()) {
-                res.push_str(span.text());
-                meta.chars += span.chars();
-                if span.is_control() {
-                    meta.control_chars += span.chars();
-                    meta.control_bytes += span.text().len();
-                }
-            }
         }
-
+        for span in self.spans().filter(|s| !s.is_control()) {
+            res.push_str(span.text());
+        }
         res
     }
 
@@ -160,13 +180,222 @@ impl<'a> TermText<'a> {
                 if span.is_control() {
                     meta.control_bytes += span.text().len();
                     meta.control_chars += span.chars();
+                } else {
+                    meta.width += span.text().width();
                 }
             }
 
+            self.metadata.set(Some(meta));
             res
         }
     }
 
+    /// Get the style at the end of the text.
+    pub fn end_style(&self) -> TextStyle {
+        self.styled_spans()
+            .last()
+            .map(|(_, s)| s)
+            .unwrap_or_default()
+    }
+
+    /// Get iterator over the displayed grapheme clusters of the text
+    /// together with their style. Control sequences (including newlines) are
+    /// skipped.
+    pub fn chars_display(&self) -> impl Iterator<Item = (&str, TextStyle)> {
+        self.styled_spans()
+            .filter(|(s, _)| !s.is_control())
+            .flat_map(|(s, st)| s.text().graphemes(true).map(move |g| (g, st)))
+    }
+
+    /// Get part of the text in the given range of display columns. Grapheme
+    /// clusters that are not whole in the range are skipped.
+    ///
+    /// The result starts with SGR sequence that sets the style active at the
+    /// start of the range and ends with reset if the style at its end is not
+    /// the default style. Control sequences within the range are kept.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> TermText<'static> {
+        let start = match range.start_bound() {
+            Bound::Included(s) => *s,
+            Bound::Excluded(s) => s.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(e) => e.saturating_add(1),
+            Bound::Excluded(e) => *e,
+            Bound::Unbounded => usize::MAX,
+        };
+
+        let mut res = String::new();
+        let mut style = TextStyle::default();
+        let mut started = false;
+        let mut col = 0;
+
+        for span in self.spans() {
+            if col >= end {
+                break;
+            }
+            if span.is_control() {
+                if started {
+                    res += span.text();
+                }
+                style.apply(span.text());
+                continue;
+            }
+            for g in span.text().graphemes(true) {
+                let w = g.width();
+                if col >= start && col + w <= end {
+                    if !started {
+                        started = true;
+                        res = style.to_sgr();
+                    }
+                    res += g;
+                }
+                col += w;
+            }
+        }
+
+        if started && !style.is_default() {
+            res += codes::RESET;
+        }
+        TermText::new(res)
+    }
+
+    /// Split the text at the given display column. See [`TermText::slice`].
+    pub fn split_at(
+        &self,
+        col: usize,
+    ) -> (TermText<'static>, TermText<'static>) {
+        (self.slice(..col), self.slice(col..))
+    }
+
+    /// Append the other text to this text. Reset is inserted between them if
+    /// this text doesn't end with the default style, so that the style of
+    /// the other text is preserved. The result ends with reset if the other
+    /// text doesn't end with the default style.
+    pub fn concat(&self, other: &TermText<'_>) -> TermText<'static> {
+        let mut res = self.as_str().to_string();
+        if !self.end_style().is_default() {
+            res += codes::RESET;
+        }
+        res += other.as_str();
+        if !other.end_style().is_default() {
+            res += codes::RESET;
+        }
+        TermText::new(res)
+    }
+
+    /// Truncate the text so that it takes at most `width` columns. If the text
+    /// is truncated, it ends with `ellipsis` (if it fits). All the control
+    /// sequences are kept, so the style at the end of the text is the same as
+    /// before truncation. The text is not split within grapheme.
+    pub fn truncate(&self, width: usize, ellipsis: &str) -> TermText<'static> {
+        if self.display_width() <= width {
+            return self.reference().to_owned();
+        }
+
+        let (ellipsis, mut avail) = match width.checked_sub(ellipsis.width()) {
+            Some(w) => (ellipsis, w),
+            None => ("", width),
+        };
+
+        let mut res = String::with_capacity(self.byte_cnt());
+        let mut cut = false;
+        for span in self.spans() {
+            if span.is_control() {
+                res += span.text();
+                continue;
+            }
+            if cut {
+                continue;
+            }
+            for g in span.text().graphemes(true) {
+                let w = g.width();
+                if w > avail {
+                    cut = true;
+                    res += ellipsis;
+                    break;
+                }
+                avail -= w;
+                res += g;
+            }
+        }
+
+        TermText::new(res)
+    }
+
+    /// Pad the text with spaces so that it takes at least `width` columns.
+    /// The padding is outside of the text.
+    pub fn pad(&self, width: usize, align: Align) -> TermText<'static> {
+        let pad = width.saturating_sub(self.display_width());
+        if pad == 0 {
+            return self.reference().to_owned();
+        }
+
+        let (left, right) = match align {
+            Align::Left => (0, pad),
+            Align::Right => (pad, 0),
+            Align::Center => (pad / 2, pad - pad / 2),
+        };
+
+        let mut res = String::with_capacity(self.byte_cnt() + pad);
+        res.extend(std::iter::repeat_n(' ', left));
+        res += self.as_str();
+        res.extend(std::iter::repeat_n(' ', right));
+        TermText::new(res)
+    }
+
+    /// Truncate or pad the text so that it takes exactly `width` columns.
+    /// See [`TermText::truncate`] and [`TermText::pad`].
+    pub fn fit(
+        &self,
+        width: usize,
+        align: Align,
+        ellipsis: &str,
+    ) -> TermText<'static> {
+        self.truncate(width, ellipsis).pad(width, align)
+    }
+
+    /// Wrap the text at word boundaries so that each line takes at most
+    /// `width` columns. Words that are longer than the line are split.
+    /// Continuation lines are indented by `indent` spaces (hanging indent).
+    ///
+    /// Lines with active style are reset at their end and the style is set
+    /// again at the start of the next line, so each line may be printed
+    /// separately. Newlines in the text are kept as line breaks.
+    pub fn wrap(&self, width: usize, indent: usize) -> Vec<TermText<'static>> {
+        wrap::wrap(self.as_str(), width, indent)
+    }
+
+    /// Convert the text to HTML `<pre>` element with inline styles. Colors
+    /// are taken from the given palette.
+    ///
+    /// SGR sequences, newlines, tabs, carriage returns, cursor movement
+    /// (`CSI A` to `CSI H`, `CSI d` and `CSI f`) and erase in line (`CSI K`)
+    /// are interpreted. Absolute cursor positions are relative to the start
+    /// of the text. Other control sequences (e.g. erase in display, scrolling
+    /// or saving the cursor) are ignored, so text that uses them may not look
+    /// as in terminal.
+    pub fn to_html(&self, palette: &Palette) -> String {
+        export::to_html(self.as_str(), Some(palette))
+    }
+
+    /// Convert the text to HTML `<pre>` element that uses classes where
+    /// possible. The CSS for the classes can be generated with
+    /// [`Palette::css`].
+    ///
+    /// The text is interpreted in the same way as with [`TermText::to_html`].
+    pub fn to_html_classes(&self) -> String {
+        export::to_html(self.as_str(), None)
+    }
+
+    /// Convert the text to SVG image that looks like terminal with the text.
+    /// Colors are taken from the given palette.
+    ///
+    /// The text is interpreted in the same way as with [`TermText::to_html`].
+    pub fn to_svg(&self, palette: &Palette) -> String {
+        export::to_svg(self.as_str(), palette)
+    }
+
     /// Get the unerlying [`Cow`]
     pub fn as_cow(&self) -> &Cow<'a, str> {
         &self.text
@@ -0,0 +1,107 @@
+use crate::Rgb;
+
+use super::Color;
+
+/// Colors used when converting styled text to other formats.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Palette {
+    /// The 16 basic colors. Colors `8..16` are the bright variants.
+    pub colors: [Rgb; 16],
+    /// Default foreground color.
+    pub fg: Rgb,
+    /// Default background color.
+    pub bg: Rgb,
+}
+
+impl Palette {
+    /// The default palette of xterm.
+    pub const XTERM: Self = Self {
+        colors: [
+            Rgb::new(0x00, 0x00, 0x00),
+            Rgb::new(0xcd, 0x00, 0x00),
+            Rgb::new(0x00, 0xcd, 0x00),
+            Rgb::new(0xcd, 0xcd, 0x00),
+            Rgb::new(0x00, 0x00, 0xee),
+            Rgb::new(0xcd, 0x00, 0xcd),
+            Rgb::new(0x00, 0xcd, 0xcd),
+            Rgb::new(0xe5, 0xe5, 0xe5),
+            Rgb::new(0x7f, 0x7f, 0x7f),
+            Rgb::new(0xff, 0x00, 0x00),
+            Rgb::new(0x00, 0xff, 0x00),
+            Rgb::new(0xff, 0xff, 0x00),
+            Rgb::new(0x5c, 0x5c, 0xff),
+            Rgb::new(0xff, 0x00, 0xff),
+            Rgb::new(0x00, 0xff, 0xff),
+            Rgb::new(0xff, 0xff, 0xff),
+        ],
+        fg: Rgb::new(0xe5, 0xe5, 0xe5),
+        bg: Rgb::new(0x00, 0x00, 0x00),
+    };
+
+    /// Get the rgb value of the color. Colors `16..256` of the 256 color
+    /// palette are the standard color cube and grayscale.
+    pub fn rgb(&self, color: Color) -> Rgb {
+        let idx = match color {
+            Color::Rgb(c) => return c,
+            Color::Basic(c) | Color::Indexed(c) => c,
+        };
+
+        match idx {
+            0..16 => self.colors[idx as usize],
+            16..232 => {
+                let level = |c: u8| if c == 0 { 0 } else { 55 + 40 * c };
+                let i = idx - 16;
+                Rgb::new(level(i / 36), level(i / 6 % 6), level(i % 6))
+            }
+            _ => {
+                let c = 8 + 10 * (idx - 232);
+                Rgb::new(c, c, c)
+            }
+        }
+    }
+
+    /// Get the foreground color, or the default foreground color.
+    pub fn fg(&self, color: Option<Color>) -> Rgb {
+        color.map_or(self.fg, |c| self.rgb(c))
+    }
+
+    /// Get the background color, or the default background color.
+    pub fn bg(&self, color: Option<Color>) -> Rgb {
+        color.map_or(self.bg, |c| self.rgb(c))
+    }
+
+    /// Get CSS with the classes used by [`TermText::to_html_classes`].
+    ///
+    /// [`TermText::to_html_classes`]: super::TermText::to_html_classes
+    pub fn css(&self) -> String {
+        let mut res = format!(
+            ".tm{{color:{};background-color:{}}}\n\
+            .tm-inverse-fg{{color:{}}}\n\
+            .tm-inverse-bg{{background-color:{}}}\n\
+            .tm-bold{{font-weight:bold}}\n\
+            .tm-faint{{opacity:0.5}}\n\
+            .tm-italic{{font-style:italic}}\n\
+            .tm-invisible{{visibility:hidden}}\n",
+            hex(self.fg),
+            hex(self.bg),
+            hex(self.bg),
+            hex(self.fg),
+        );
+        for (i, c) in self.colors.iter().enumerate() {
+            res += &format!(".tm-fg-{i}{{color:{}}}\n", hex(*c));
+            res += &format!(".tm-bg-{i}{{background-color:{}}}\n", hex(*c));
+        }
+        res
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::XTERM
+    }
+}
+
+/// Format the color as CSS hex color.
+pub(super) fn hex(c: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b)
+}
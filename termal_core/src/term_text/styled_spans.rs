@@ -0,0 +1,42 @@
+use super::{TermTextSpan, TermTextSpans, TextStyle};
+
+/// Iterator over spans of string together with the style of each span.
+pub struct StyledSpans<'a> {
+    spans: TermTextSpans<'a>,
+    style: TextStyle,
+}
+
+impl<'a> StyledSpans<'a> {
+    /// Create new iterator over spans of string. The string starts with the
+    /// given style.
+    pub fn new(text: &'a str, style: TextStyle) -> Self {
+        Self {
+            spans: TermTextSpans::new(text),
+            style,
+        }
+    }
+
+    /// Get the style after the last returned span.
+    pub fn style(&self) -> TextStyle {
+        self.style
+    }
+
+    /// Get the remaining spans as a string.
+    pub fn as_str(&self) -> &'a str {
+        self.spans.as_str()
+    }
+}
+
+impl<'a> Iterator for StyledSpans<'a> {
+    /// The span and the style after the span. For text spans it is the style
+    /// of the text.
+    type Item = (TermTextSpan<'a>, TextStyle);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.spans.next()?;
+        if span.is_control() {
+            self.style.apply(span.text());
+        }
+        Some((span, self.style))
+    }
+}
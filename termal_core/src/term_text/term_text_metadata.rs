@@ -1,3 +1,5 @@
+use unicode_width::UnicodeWidthStr;
+
 use super::TermTextSpans;
 
 /// Information about text with control sequences.
@@ -9,6 +11,10 @@ pub struct TermTextMetadata {
     pub control_chars: usize,
     /// Number of bytes from control sequences.
     pub control_bytes: usize,
+    /// Number of terminal columns taken by the display characters. Wide
+    /// characters (e.g. CJK) take two columns and combining characters take
+    /// no columns.
+    pub width: usize,
 }
 
 impl TermTextMetadata {
@@ -26,6 +32,8 @@ impl TermTextMetadata {
             if span.is_control() {
                 self.control_chars += span.chars();
                 self.control_bytes += span.text().len();
+            } else {
+                self.width += span.text().width();
             }
         }
     }
@@ -0,0 +1,260 @@
+use crate::{Rgb, codes};
+
+/// Color set with SGR sequence.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Color {
+    /// One of the 16 basic colors (`0..16`). Colors `8..16` are the bright
+    /// variants. Larger values are written as [`Color::Indexed`].
+    Basic(u8),
+    /// Color from the 256 color palette.
+    Indexed(u8),
+    /// True rgb color.
+    Rgb(Rgb),
+}
+
+/// Style of underline.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Underline {
+    /// There is no underline.
+    #[default]
+    None,
+    /// Single line underline.
+    Single,
+    /// Double line underline.
+    Double,
+    /// Curly (wavy) underline.
+    Curly,
+    /// Dotted underline.
+    Dotted,
+    /// Dashed underline.
+    Dashed,
+}
+
+/// Text style set by SGR (select graphic rendition) sequences.
+///
+/// Default value is the style after [`codes::RESET`]. `None` colors are the
+/// default colors of the terminal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct TextStyle {
+    /// Foreground color.
+    pub fg: Option<Color>,
+    /// Background color.
+    pub bg: Option<Color>,
+    /// Color of the underline.
+    pub underline_color: Option<Color>,
+    /// Bold text. May be combined with faint.
+    pub bold: bool,
+    /// Faint (dim) text.
+    pub faint: bool,
+    /// Italic text.
+    pub italic: bool,
+    /// Style of the underline.
+    pub underline: Underline,
+    /// Blinking text.
+    pub blinking: bool,
+    /// Foreground and background colors are swapped.
+    pub inverse: bool,
+    /// Invisible text.
+    pub invisible: bool,
+    /// Crossed out text.
+    pub striketrough: bool,
+    /// Line above the text.
+    pub overline: bool,
+}
+
+impl TextStyle {
+    /// Checks whether this is the default style.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the given control sequence to the style. Returns `false` if the
+    /// sequence is not SGR sequence. Then the style is not changed.
+    pub fn apply(&mut self, seq: &str) -> bool {
+        let Some(args) = seq
+            .strip_prefix(codes::CSI)
+            .and_then(|s| s.strip_suffix('m'))
+        else {
+            return false;
+        };
+        if !args
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == ';' || c == ':')
+        {
+            return false;
+        }
+
+        self.apply_args(args);
+        true
+    }
+
+    /// Apply the arguments of SGR sequence (the part between `CSI` and `m`).
+    /// Both `;` and `:` separated arguments are supported.
+    pub fn apply_args(&mut self, args: &str) {
+        let mut args = args.split(';').map(|a| {
+            a.split(':')
+                .map(|s| s.parse::<u32>().unwrap_or_default())
+                .collect::<Vec<_>>()
+        });
+
+        while let Some(arg) = args.next() {
+            let sub = &arg[1..];
+            match arg[0] {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                2 => self.faint = true,
+                3 => self.italic = true,
+                4 => {
+                    self.underline = match sub.first() {
+                        None | Some(1) => Underline::Single,
+                        Some(2) => Underline::Double,
+                        Some(3) => Underline::Curly,
+                        Some(4) => Underline::Dotted,
+                        Some(5) => Underline::Dashed,
+                        _ => Underline::None,
+                    }
+                }
+                5 | 6 => self.blinking = true,
+                7 => self.inverse = true,
+                8 => self.invisible = true,
+                9 => self.striketrough = true,
+                21 => self.underline = Underline::Double,
+                22 => {
+                    self.bold = false;
+                    self.faint = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = Underline::None,
+                25 => self.blinking = false,
+                27 => self.inverse = false,
+                28 => self.invisible = false,
+                29 => self.striketrough = false,
+                c @ 30..=37 => self.fg = Some(Color::Basic(c as u8 - 30)),
+                38 => self.fg = parse_color(sub, &mut args).or(self.fg),
+                39 => self.fg = None,
+                c @ 40..=47 => self.bg = Some(Color::Basic(c as u8 - 40)),
+                48 => self.bg = parse_color(sub, &mut args).or(self.bg),
+                49 => self.bg = None,
+                53 => self.overline = true,
+                55 => self.overline = false,
+                58 => {
+                    self.underline_color =
+                        parse_color(sub, &mut args).or(self.underline_color)
+                }
+                59 => self.underline_color = None,
+                c @ 90..=97 => self.fg = Some(Color::Basic(c as u8 - 82)),
+                c @ 100..=107 => self.bg = Some(Color::Basic(c as u8 - 92)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Get SGR sequence that sets this style when the current style is the
+    /// default style. Returns empty string for the default style.
+    pub fn to_sgr(&self) -> String {
+        let mut args = String::new();
+        let mut add = |a: &str| {
+            if !args.is_empty() {
+                args.push(';');
+            }
+            args += a;
+        };
+
+        if self.bold {
+            add("1");
+        }
+        if self.faint {
+            add("2");
+        }
+        if self.italic {
+            add("3");
+        }
+        match self.underline {
+            Underline::None => {}
+            Underline::Single => add("4"),
+            Underline::Double => add("21"),
+            Underline::Curly => add("4:3"),
+            Underline::Dotted => add("4:4"),
+            Underline::Dashed => add("4:5"),
+        }
+        if self.blinking {
+            add("5");
+        }
+        if self.inverse {
+            add("7");
+        }
+        if self.invisible {
+            add("8");
+        }
+        if self.striketrough {
+            add("9");
+        }
+        if self.overline {
+            add("53");
+        }
+        if let Some(c) = self.fg {
+            add(&color_args(c, 30, 90, 38));
+        }
+        if let Some(c) = self.bg {
+            add(&color_args(c, 40, 100, 48));
+        }
+        if let Some(c) = self.underline_color {
+            let c = match c {
+                Color::Basic(c) => Color::Indexed(c),
+                c => c,
+            };
+            add(&color_args(c, 0, 0, 58));
+        }
+
+        if args.is_empty() {
+            args
+        } else {
+            format!("{}{args}m", codes::CSI)
+        }
+    }
+}
+
+/// Parse extended color (`38`, `48` or `58`). `sub` are the `:` separated
+/// arguments. If there are none, the `;` separated arguments are taken from
+/// `args`. Returns `None` if the color is not supported or if any of the
+/// values is larger than 255.
+fn parse_color(
+    sub: &[u32],
+    args: &mut impl Iterator<Item = Vec<u32>>,
+) -> Option<Color> {
+    let mut next = || args.next().map_or(0, |a| a[0]);
+    let byte = |v: u32| u8::try_from(v).ok();
+
+    if let Some(&kind) = sub.first() {
+        return match kind {
+            5 => Some(Color::Indexed(byte(*sub.get(1)?)?)),
+            // The color space id is optional.
+            2 if sub.len() >= 4 => {
+                let [r, g, b] = sub[sub.len() - 3..] else {
+                    unreachable!()
+                };
+                Some(Color::Rgb(Rgb::new(byte(r)?, byte(g)?, byte(b)?)))
+            }
+            _ => None,
+        };
+    }
+
+    match next() {
+        5 => Some(Color::Indexed(byte(next())?)),
+        2 => {
+            let (r, g, b) = (next(), next(), next());
+            Some(Color::Rgb(Rgb::new(byte(r)?, byte(g)?, byte(b)?)))
+        }
+        _ => None,
+    }
+}
+
+fn color_args(c: Color, basic: u8, bright: u8, ext: u8) -> String {
+    match c {
+        Color::Basic(c) if c < 8 => (basic + c).to_string(),
+        Color::Basic(c) if c < 16 => (bright + c - 8).to_string(),
+        Color::Basic(c) => format!("{ext};5;{c}"),
+        Color::Indexed(c) => format!("{ext};5;{c}"),
+        Color::Rgb(Rgb { r, g, b }) => format!("{ext};2;{r};{g};{b}"),
+    }
+}
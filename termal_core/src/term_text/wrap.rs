@@ -0,0 +1,167 @@
+use std::{iter::repeat_n, mem};
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::codes;
+
+use super::{TermText, TermTextSpans, TextStyle};
+
+/// Wrap the text at word boundaries. See [`TermText::wrap`].
+pub(super) fn wrap(
+    text: &str,
+    width: usize,
+    indent: usize,
+) -> Vec<TermText<'static>> {
+    let mut w = Wrapper::new(width, indent);
+
+    for span in TermTextSpans::new(text) {
+        let s = span.text();
+        if !span.is_control() {
+            for (i, part) in s.split(' ').enumerate() {
+                if i != 0 {
+                    w.space();
+                }
+                if !part.is_empty() {
+                    w.word.push((part, false));
+                    w.word_width += part.width();
+                }
+            }
+        } else if s == "\n" {
+            w.newline();
+        } else if s.chars().all(|c| c.is_whitespace()) {
+            w.space();
+        } else {
+            w.word.push((s, true));
+        }
+    }
+
+    w.finish()
+}
+
+struct Wrapper<'a> {
+    width: usize,
+    indent: usize,
+    lines: Vec<TermText<'static>>,
+    line: String,
+    /// Width of the current line including the indent.
+    line_width: usize,
+    /// There is no visible text on the current line.
+    line_empty: bool,
+    /// The current line was created by wrapping.
+    wrapped: bool,
+    /// Style at the end of the current line.
+    style: TextStyle,
+    /// Number of spaces before the pending word.
+    spaces: usize,
+    /// Spans of the pending word. `true` for control sequences.
+    word: Vec<(&'a str, bool)>,
+    word_width: usize,
+}
+
+impl<'a> Wrapper<'a> {
+    fn new(width: usize, indent: usize) -> Self {
+        Self {
+            width,
+            indent,
+            lines: vec![],
+            line: String::new(),
+            line_width: 0,
+            line_empty: true,
+            wrapped: false,
+            style: TextStyle::default(),
+            spaces: 0,
+            word: vec![],
+            word_width: 0,
+        }
+    }
+
+    fn space(&mut self) {
+        if self.word_width != 0 {
+            self.flush_word();
+        }
+        self.spaces += 1;
+    }
+
+    fn newline(&mut self) {
+        self.flush_word();
+        self.spaces = 0;
+        self.end_line();
+        self.wrapped = false;
+        self.line += &self.style.to_sgr();
+    }
+
+    fn finish(mut self) -> Vec<TermText<'static>> {
+        self.flush_word();
+        self.end_line();
+        self.lines
+    }
+
+    /// Move the pending word to the line. Words that don't fit on the line
+    /// are moved to the next line. Words that don't fit on a line at all are
+    /// split.
+    fn flush_word(&mut self) {
+        if self.word_width == 0 {
+            // Only control sequences, keep the spaces for the next word.
+            for (s, _) in mem::take(&mut self.word) {
+                self.push_control(s);
+            }
+            return;
+        }
+
+        let mut spaces = mem::take(&mut self.spaces);
+        if self.wrapped && self.line_empty {
+            spaces = 0;
+        }
+        if !self.line_empty
+            && self.line_width + spaces + self.word_width > self.width
+        {
+            self.break_line();
+            spaces = 0;
+        }
+
+        self.line.extend(repeat_n(' ', spaces));
+        self.line_width += spaces;
+
+        for (s, control) in mem::take(&mut self.word) {
+            if control {
+                self.push_control(s);
+                continue;
+            }
+            for g in s.graphemes(true) {
+                let w = g.width();
+                if !self.line_empty && self.line_width + w > self.width {
+                    self.break_line();
+                }
+                self.line += g;
+                self.line_width += w;
+                self.line_empty = false;
+            }
+        }
+        self.word_width = 0;
+    }
+
+    fn push_control(&mut self, s: &'a str) {
+        self.line += s;
+        self.style.apply(s);
+    }
+
+    /// End the current line and start continuation line with the indent
+    /// and the current style.
+    fn break_line(&mut self) {
+        self.end_line();
+        self.wrapped = true;
+        self.line.extend(repeat_n(' ', self.indent));
+        self.line_width = self.indent;
+        self.line += &self.style.to_sgr();
+    }
+
+    fn end_line(&mut self) {
+        if !self.style.is_default() {
+            self.line += codes::RESET;
+        }
+        self.lines.push(TermText::new(mem::take(&mut self.line)));
+        self.line_width = 0;
+        self.line_empty = true;
+    }
+}
@@ -1,8 +1,11 @@
-use std::io::{BufRead, Read, Write};
+use std::{
+    io::{BufRead, Read, Write},
+    task::{Context, Poll},
+};
 
 use termal::{
     error::Result,
-    raw::{IoProvider, ValueOrMut, WaitForIn},
+    raw::{AsyncWaitForIn, IoProvider, ValueOrMut, WaitForIn},
 };
 
 pub struct BufProvider {
@@ -95,6 +98,12 @@ impl WaitForIn for BufProvider {
     }
 }
 
+impl AsyncWaitForIn for BufProvider {
+    fn poll_for_in(&self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
 impl IoProvider for BufProvider {
     type Out = Self;
     type In = Self;
@@ -1,9 +1,15 @@
+use std::time::{Duration, Instant};
+
 use termal::{
     Rgb,
-    raw::events::{
-        AmbigousEvent, AnyEvent, Event, Key, KeyCode, Modifiers, StateChange,
-        Status, TermAttr, TermFeatures, TermType,
-        mouse::{self, Mouse},
+    raw::{
+        TermSize,
+        events::{
+            AmbigousEvent, AnyEvent, Event, Key, KeyCode, Modifiers,
+            StateChange, Status, TermAttr, TermFeatures, TermType,
+            gesture::{Gesture, Recognizer},
+            mouse::{self, Mouse},
+        },
     },
 };
 
@@ -64,14 +70,16 @@ fn test_constructors() {
             event: mouse::Event::Up,
             modifiers: Modifiers::ALT,
             x: 5,
-            y: 7
+            y: 7,
+            pixels: false,
         }),
         AmbigousEvent::event(Event::Mouse(Mouse {
             button: mouse::Button::Left,
             event: mouse::Event::Up,
             modifiers: Modifiers::ALT,
             x: 5,
-            y: 7
+            y: 7,
+            pixels: false,
         }))
     );
 
@@ -190,6 +198,11 @@ fn test_key() {
         AmbigousEvent::from_code(b"\x1b[1;9P"),
         AmbigousEvent::key(Key::mcode(KeyCode::F1, Modifiers::META)),
     );
+
+    assert_eq!(
+        AmbigousEvent::from_code(b"\x1b[Z"),
+        AmbigousEvent::key(Key::mcode(KeyCode::Tab, Modifiers::SHIFT)),
+    );
 }
 
 #[test]
@@ -232,6 +245,7 @@ fn test_mouse() {
             event: mouse::Event::Down,
             x: 8,
             y: 15,
+            pixels: false,
         })
     );
 
@@ -243,6 +257,7 @@ fn test_mouse() {
             event: mouse::Event::Down,
             x: 8,
             y: 15,
+            pixels: false,
         })
     );
 
@@ -254,6 +269,7 @@ fn test_mouse() {
             event: mouse::Event::ScrollDown,
             x: 8,
             y: 15,
+            pixels: false,
         })
     );
 
@@ -262,9 +278,10 @@ fn test_mouse() {
         AmbigousEvent::mouse(Mouse {
             button: mouse::Button::Middle,
             modifiers: Modifiers::SHIFT,
-            event: mouse::Event::Move,
+            event: mouse::Event::Drag,
             x: 8,
             y: 15,
+            pixels: false,
         })
     );
 
@@ -278,6 +295,7 @@ fn test_mouse() {
             event: mouse::Event::Move,
             x: 1500,
             y: 15,
+            pixels: false,
         })
     );
 
@@ -291,6 +309,7 @@ fn test_mouse() {
             event: mouse::Event::Up,
             x: 8,
             y: 15,
+            pixels: false,
         })
     );
 
@@ -302,6 +321,7 @@ fn test_mouse() {
             event: mouse::Event::Down,
             x: 8,
             y: 15,
+            pixels: false,
         })
     );
 
@@ -313,6 +333,7 @@ fn test_mouse() {
             event: mouse::Event::ScrollDown,
             x: 8,
             y: 15,
+            pixels: false,
         })
     );
 
@@ -321,9 +342,10 @@ fn test_mouse() {
         AmbigousEvent::mouse(Mouse {
             button: mouse::Button::Middle,
             modifiers: Modifiers::SHIFT,
-            event: mouse::Event::Move,
+            event: mouse::Event::Drag,
             x: 8,
             y: 15,
+            pixels: false,
         })
     );
 
@@ -337,6 +359,7 @@ fn test_mouse() {
             event: mouse::Event::Down,
             x: 8,
             y: 15,
+            pixels: false,
         })
     );
 
@@ -348,6 +371,7 @@ fn test_mouse() {
             event: mouse::Event::Down,
             x: 8,
             y: 15,
+            pixels: false,
         })
     );
 
@@ -359,6 +383,7 @@ fn test_mouse() {
             event: mouse::Event::ScrollDown,
             x: 8,
             y: 15,
+            pixels: false,
         })
     );
 
@@ -367,13 +392,260 @@ fn test_mouse() {
         AmbigousEvent::mouse(Mouse {
             button: mouse::Button::Middle,
             modifiers: Modifiers::SHIFT,
-            event: mouse::Event::Move,
+            event: mouse::Event::Drag,
             x: 8,
             y: 15,
+            pixels: false,
         })
     );
 }
 
+#[test]
+fn test_mouse_extra() {
+    fn mouse(
+        button: mouse::Button,
+        event: mouse::Event,
+        modifiers: Modifiers,
+    ) -> AmbigousEvent {
+        AmbigousEvent::mouse(Mouse {
+            button,
+            event,
+            modifiers,
+            x: 8,
+            y: 15,
+            pixels: false,
+        })
+    }
+
+    // Normal mode
+
+    assert_eq!(
+        AmbigousEvent::from_code(b"\x1b[M\xA0\x28\x2F"),
+        mouse(mouse::Button::Back, mouse::Event::Down, Modifiers::NONE),
+    );
+
+    assert_eq!(
+        AmbigousEvent::from_code(b"\x1b[M\x62\x28\x2F"),
+        mouse(
+            mouse::Button::None,
+            mouse::Event::ScrollLeft,
+            Modifiers::NONE
+        ),
+    );
+
+    assert_eq!(
+        AmbigousEvent::from_code(b"\x1b[M\x43\x28\x2F"),
+        mouse(mouse::Button::None, mouse::Event::Move, Modifiers::NONE),
+    );
+
+    // UTF-8
+
+    assert_eq!(
+        AmbigousEvent::from_code("\x1b[M\u{a3}\x28\x2F".as_bytes()),
+        mouse(mouse::Button::Button11, mouse::Event::Down, Modifiers::NONE),
+    );
+
+    // SGR
+
+    assert_eq!(
+        AmbigousEvent::from_code(b"\x1b[<129;8;15M"),
+        mouse(mouse::Button::Forward, mouse::Event::Down, Modifiers::NONE),
+    );
+
+    assert_eq!(
+        AmbigousEvent::from_code(b"\x1b[<129;8;15m"),
+        mouse(mouse::Button::Forward, mouse::Event::Up, Modifiers::NONE),
+    );
+
+    assert_eq!(
+        AmbigousEvent::from_code(b"\x1b[<163;8;15M"),
+        mouse(mouse::Button::Button11, mouse::Event::Drag, Modifiers::NONE),
+    );
+
+    assert_eq!(
+        AmbigousEvent::from_code(b"\x1b[<83;8;15M"),
+        mouse(
+            mouse::Button::None,
+            mouse::Event::ScrollRight,
+            Modifiers::CONTROL
+        ),
+    );
+
+    assert_eq!(
+        AmbigousEvent::from_code(b"\x1b[<32;8;15M"),
+        mouse(mouse::Button::Left, mouse::Event::Drag, Modifiers::NONE),
+    );
+
+    assert_eq!(
+        AmbigousEvent::from_code(b"\x1b[<35;8;15M"),
+        mouse(mouse::Button::None, mouse::Event::Move, Modifiers::NONE),
+    );
+
+    // URXVT
+
+    assert_eq!(
+        AmbigousEvent::from_code(b"\x1b[162;8;15M"),
+        mouse(mouse::Button::Button10, mouse::Event::Down, Modifiers::NONE),
+    );
+
+    assert_eq!(
+        AmbigousEvent::from_code(b"\x1b[98;8;15M"),
+        mouse(
+            mouse::Button::None,
+            mouse::Event::ScrollLeft,
+            Modifiers::NONE
+        ),
+    );
+}
+
+#[test]
+fn test_mouse_pixels() {
+    let evt = AmbigousEvent::from_code_mouse(
+        b"\x1b[<0;161;321M",
+        Some(mouse::Encoding::SgrPixels),
+    );
+    let pix = Mouse {
+        button: mouse::Button::Left,
+        event: mouse::Event::Down,
+        modifiers: Modifiers::NONE,
+        x: 161,
+        y: 321,
+        pixels: true,
+    };
+    assert_eq!(evt, AmbigousEvent::mouse(pix));
+
+    assert_eq!(
+        AmbigousEvent::from_code_mouse(
+            b"\x1b[<0;161;321M",
+            Some(mouse::Encoding::Sgr)
+        ),
+        AmbigousEvent::from_code(b"\x1b[<0;161;321M"),
+    );
+
+    let cells = Mouse {
+        x: 17,
+        y: 17,
+        pixels: false,
+        ..pix
+    };
+    assert_eq!(pix.to_cells(10, 20), cells);
+    assert_eq!(cells.to_cells(10, 20), cells);
+    assert_eq!(
+        pix.to_cells_by_char_size(&Status::CharSize { w: 10, h: 20 }),
+        Some(cells)
+    );
+    assert_eq!(pix.to_cells_by_char_size(&Status::Ok), None);
+    assert_eq!(
+        pix.to_cells_by_term_size(&TermSize {
+            char_width: 80,
+            char_height: 24,
+            pixel_width: 800,
+            pixel_height: 480,
+        }),
+        Some(cells)
+    );
+}
+
+#[test]
+fn test_gestures() {
+    fn mouse(button: mouse::Button, event: mouse::Event, x: usize) -> Mouse {
+        Mouse {
+            button,
+            event,
+            modifiers: Modifiers::NONE,
+            x,
+            y: 1,
+            pixels: false,
+        }
+    }
+
+    use mouse::{
+        Button::{Left, Right},
+        Event::*,
+    };
+
+    let mut rec = Recognizer::default();
+    let t = Instant::now();
+    let ms = |m| t + Duration::from_millis(m);
+
+    // Single, double and triple click.
+    for (i, count) in [1, 2, 3, 1].into_iter().enumerate() {
+        let time = ms(i as u64 * 100);
+        assert_eq!(rec.handle_mouse_at(&mouse(Left, Down, 5), time), None);
+        assert_eq!(
+            rec.handle_mouse_at(&mouse(mouse::Button::None, Up, 5), time),
+            Some(Gesture::Click {
+                button: Left,
+                modifiers: Modifiers::NONE,
+                x: 5,
+                y: 1,
+                count
+            })
+        );
+    }
+
+    // Too late for double click.
+    rec.handle_mouse_at(&mouse(Left, Down, 5), ms(1000));
+    assert!(matches!(
+        rec.handle_mouse_at(&mouse(Left, Up, 5), ms(1000)),
+        Some(Gesture::Click { count: 1, .. })
+    ));
+
+    // Drag
+    assert_eq!(rec.handle_mouse_at(&mouse(Right, Down, 2), ms(2000)), None);
+    assert_eq!(rec.handle_mouse_at(&mouse(Right, Drag, 2), ms(2000)), None);
+    assert!(matches!(
+        rec.handle_mouse_at(&mouse(Right, Drag, 3), ms(2000)),
+        Some(Gesture::DragStart {
+            button: Right,
+            origin: (2, 1),
+            x: 3,
+            ..
+        })
+    ));
+    assert!(matches!(
+        rec.handle_mouse_at(&mouse(Right, Drag, 4), ms(2000)),
+        Some(Gesture::DragMove {
+            button: Right,
+            origin: (2, 1),
+            x: 4,
+            ..
+        })
+    ));
+    assert!(matches!(
+        rec.handle_event(&Event::Mouse(mouse(mouse::Button::None, Up, 6))),
+        Some(Gesture::DragEnd {
+            button: Right,
+            origin: (2, 1),
+            x: 6,
+            ..
+        })
+    ));
+
+    // Wheel acceleration
+    let deltas: Vec<_> = (0..10)
+        .map(|i| {
+            match rec.handle_mouse_at(
+                &mouse(mouse::Button::None, ScrollDown, 1),
+                ms(3000 + i),
+            ) {
+                Some(Gesture::Wheel { delta, .. }) => delta,
+                _ => 0,
+            }
+        })
+        .collect();
+    assert_eq!(deltas, [1, 1, 1, 1, 2, 2, 2, 2, 3, 3]);
+    assert!(matches!(
+        rec.handle_mouse_at(
+            &mouse(mouse::Button::None, ScrollUp, 1),
+            ms(3010)
+        ),
+        Some(Gesture::Wheel { delta: 1, .. })
+    ));
+
+    assert_eq!(rec.handle_event(&Event::Focus), None);
+}
+
 #[test]
 fn test_status() {
     assert_eq!(
@@ -1,8 +1,10 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, ops::Bound};
 
 use termal::{
-    formatc,
-    term_text::{TermText, TermTextSpan},
+    Rgb, formatc,
+    term_text::{
+        Align, Color, Palette, TermText, TermTextSpan, TextStyle, Underline,
+    },
 };
 
 #[test]
@@ -32,3 +34,225 @@ fn test_term_text() {
     assert_eq!(sf(&text, |c| c.is_control()), formatc!("{'r}{'_}"));
     assert_eq!(sf(&text, |c| !c.is_control()), "Textíček");
 }
+
+#[test]
+fn test_display_width() {
+    let text = TermText::new(formatc!("{'r}漢字{'_}e\u{301}!"));
+    assert_eq!(text.display_char_cnt(), 5);
+    assert_eq!(text.display_width(), 6);
+}
+
+#[test]
+fn test_truncate_pad() {
+    let text = TermText::new(formatc!("{'r}漢字{'_}abc"));
+    assert_eq!(text.truncate(7, "…").as_str(), text.as_str());
+    assert_eq!(text.truncate(6, "…").as_str(), formatc!("{'r}漢字{'_}a…"));
+    assert_eq!(text.truncate(4, "…").as_str(), formatc!("{'r}漢…{'_}"));
+    assert_eq!(text.truncate(3, "…").as_str(), formatc!("{'r}漢…{'_}"));
+    assert_eq!(text.truncate(0, "…").as_str(), formatc!("{'r}{'_}"));
+    assert_eq!(text.truncate(5, "").display_width(), 5);
+
+    let text = TermText::new(formatc!("{'r}ab{'_}"));
+    assert_eq!(text.pad(5, Align::Left).as_str(), formatc!("{'r}ab{'_}   "));
+    assert_eq!(
+        text.pad(5, Align::Right).as_str(),
+        formatc!("   {'r}ab{'_}")
+    );
+    assert_eq!(
+        text.pad(5, Align::Center).as_str(),
+        formatc!(" {'r}ab{'_}  ")
+    );
+    assert_eq!(text.pad(1, Align::Right).as_str(), text.as_str());
+
+    let text = TermText::new("abcdef");
+    assert_eq!(text.fit(4, Align::Left, "…").as_str(), "abc…");
+    assert_eq!(text.fit(8, Align::Right, "…").as_str(), "  abcdef");
+}
+
+#[test]
+fn test_wrap() {
+    fn wrap(s: &str, width: usize, indent: usize) -> Vec<String> {
+        TermText::new(s)
+            .wrap(width, indent)
+            .iter()
+            .map(|l| l.as_str().to_string())
+            .collect()
+    }
+
+    assert_eq!(wrap("hello there world", 11, 0), ["hello there", "world"]);
+    assert_eq!(
+        wrap("hello there world", 10, 2),
+        ["hello", "  there", "  world"]
+    );
+    assert_eq!(wrap("abcdefgh ij", 3, 0), ["abc", "def", "gh", "ij"]);
+    assert_eq!(wrap("ab\n\ncd ef", 4, 1), ["ab", "", "cd", " ef"]);
+    assert_eq!(wrap("漢字 漢字", 5, 0), ["漢字", "漢字"]);
+
+    assert_eq!(
+        wrap(&formatc!("a {'r}bc de{'_} f"), 5, 1),
+        [formatc!("a {'r}bc{'_}"), formatc!(" {'r}de{'_} f")]
+    );
+    assert_eq!(
+        wrap(&formatc!("{'r}abcdef{'_}"), 3, 0),
+        [formatc!("{'r}abc{'_}"), formatc!("{'r}def{'_}")]
+    );
+}
+
+#[test]
+fn test_text_style() {
+    let text = TermText::new(
+        "\x1b[1;31mab\x1b[4:3;48;5;200mc\x1b[22;38:2::1:2:3md\x1b[0;92;58;2;4;5;6me\x1b[2Jf\x1b[mg",
+    );
+    let styles: Vec<_> = text
+        .styled_spans()
+        .filter(|(s, _)| !s.is_control())
+        .map(|(s, st)| (s.text(), st))
+        .collect();
+
+    let red = TextStyle {
+        fg: Some(Color::Basic(1)),
+        bold: true,
+        ..Default::default()
+    };
+    let curly = TextStyle {
+        bg: Some(Color::Indexed(200)),
+        underline: Underline::Curly,
+        ..red
+    };
+    let rgb = TextStyle {
+        fg: Some(Color::Rgb(Rgb::new(1, 2, 3))),
+        bold: false,
+        ..curly
+    };
+    let green = TextStyle {
+        fg: Some(Color::Basic(10)),
+        underline_color: Some(Color::Rgb(Rgb::new(4, 5, 6))),
+        ..Default::default()
+    };
+    assert_eq!(
+        styles,
+        [
+            ("ab", red),
+            ("c", curly),
+            ("d", rgb),
+            ("e", green),
+            ("f", green),
+            ("g", TextStyle::default()),
+        ]
+    );
+
+    assert_eq!(red.to_sgr(), "\x1b[1;31m");
+    assert_eq!(curly.to_sgr(), "\x1b[1;4:3;31;48;5;200m");
+    assert_eq!(green.to_sgr(), "\x1b[92;58;2;4;5;6m");
+    assert_eq!(TextStyle::default().to_sgr(), "");
+
+    let mut style = TextStyle::default();
+    assert!(!style.apply("\x1b[2J"));
+    assert!(style.apply(&rgb.to_sgr()));
+    assert_eq!(style, rgb);
+
+    // Values larger than 255 are ignored.
+    assert!(style.apply("\x1b[38;5;256;48;2;1;300;3m"));
+    assert_eq!(style, rgb);
+    assert!(style.apply("\x1b[38:5:1000;48:2:1:2:256m"));
+    assert_eq!(style, rgb);
+
+    // Too large basic colors are written as indexed.
+    let basic = TextStyle {
+        fg: Some(Color::Basic(200)),
+        bg: Some(Color::Basic(255)),
+        ..Default::default()
+    };
+    assert_eq!(basic.to_sgr(), "\x1b[38;5;200;48;5;255m");
+}
+
+#[test]
+fn test_export() {
+    let text = TermText::new(formatc!("a<{'r}b{'_}\n{'inverse}c{'_}"));
+
+    assert_eq!(
+        text.to_html(&Palette::XTERM),
+        "<pre class=\"tm\" style=\"color:#e5e5e5;background-color:#000000\">\
+        a&lt;<span style=\"color:#ff0000;\">b</span>\n\
+        <span style=\"color:#000000;background-color:#e5e5e5;\">c</span>\
+        </pre>"
+    );
+    assert_eq!(
+        text.to_html_classes(),
+        "<pre class=\"tm\">a&lt;<span class=\"tm-fg-9\">b</span>\n\
+        <span class=\"tm-inverse-fg tm-inverse-bg\">c</span></pre>"
+    );
+    assert!(Palette::XTERM.css().contains(".tm-fg-9{color:#ff0000}"));
+
+    let svg = text.to_svg(&Palette::XTERM);
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains(">a&lt;</text>"));
+    assert!(svg.contains("fill=\"#ff0000\" xml:space=\"preserve\">b</text>"));
+    assert!(svg.contains("fill=\"#e5e5e5\"/>"));
+
+    // Carriage return overwrites the line and erase in line removes the rest.
+    let text = TermText::new("50%\r100%\rdone\x1b[K\n\t漢b\rx\n漢漢\r a");
+    assert_eq!(
+        text.to_html_classes(),
+        "<pre class=\"tm\">done\nx       漢b\n a漢</pre>"
+    );
+
+    // Cursor movement moves where the following text is written.
+    let text = TermText::new("ab\ncd\x1b[A\x1b[DX\x1b[2;4HY\x1b[1GZ\x1b[2B!");
+    assert_eq!(
+        text.to_html_classes(),
+        "<pre class=\"tm\">aX\nZd Y\n\n !</pre>"
+    );
+
+    assert_eq!(Palette::XTERM.rgb(Color::Indexed(196)), Rgb::new(255, 0, 0));
+    assert_eq!(Palette::XTERM.rgb(Color::Indexed(232)), Rgb::new(8, 8, 8));
+}
+
+#[test]
+fn test_slice() {
+    let text = TermText::new(formatc!("ab{'r}c漢d{'_}e"));
+    assert_eq!(text.slice(..2).as_str(), "ab");
+    assert_eq!(text.slice(1..4).as_str(), formatc!("b{'r}c{'_}"));
+    assert_eq!(text.slice(3..).as_str(), formatc!("{'r}漢d{'_}e"));
+    assert_eq!(text.slice(4..6).as_str(), formatc!("{'r}d{'_}"));
+    assert_eq!(text.slice(2..=2).as_str(), formatc!("{'r}c{'_}"));
+    assert_eq!(text.slice(10..).as_str(), "");
+    assert_eq!(text.slice(..=usize::MAX).as_str(), text.as_str());
+    assert_eq!(
+        text.slice((Bound::Excluded(usize::MAX), Bound::Unbounded))
+            .as_str(),
+        ""
+    );
+
+    let (a, b) = text.split_at(3);
+    assert_eq!(a.as_str(), formatc!("ab{'r}c{'_}"));
+    assert_eq!(b.as_str(), formatc!("{'r}漢d{'_}e"));
+    assert_eq!(a.concat(&b).strip_control(), "abc漢de");
+
+    let a = TermText::new(formatc!("{'r}a"));
+    assert_eq!(a.end_style().fg, Some(Color::Basic(9)));
+    assert_eq!(
+        a.concat(&TermText::new("b")).as_str(),
+        formatc!("{'r}a{'_}b")
+    );
+    assert_eq!(
+        TermText::new("b").concat(&a).as_str(),
+        formatc!("b{'r}a{'_}")
+    );
+
+    let chars: Vec<_> = text
+        .chars_display()
+        .map(|(c, s)| (c, s.fg.is_some()))
+        .collect();
+    assert_eq!(
+        chars,
+        [
+            ("a", false),
+            ("b", false),
+            ("c", true),
+            ("漢", true),
+            ("d", true),
+            ("e", false)
+        ]
+    );
+}
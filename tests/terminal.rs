@@ -1,9 +1,29 @@
-use std::time::Duration;
+use std::{
+    cell::RefCell,
+    io::Write,
+    rc::Rc,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
 
 use common::BufProvider;
 use termal::{
+    codes::Selection,
     error::Error,
-    raw::{Terminal, events::AmbigousEvent},
+    formatc,
+    raw::{
+        DEFAULT_CLIPBOARD_LIMIT, MockProvider, Screen, Terminal, TestTerminal,
+        events::{
+            AmbigousEvent, Event, Key, KeyCode, Modifiers, PasteMode,
+            StateChange, Status,
+            mouse::{self, Mouse},
+        },
+        readers::{
+            Command, Echo, FileHistory, History, Keymap, MemHistory, ReadConf,
+            TermRead, Validation, WordCompleter,
+        },
+    },
 };
 
 mod common;
@@ -139,3 +159,778 @@ fn test_events() {
     assert_eq!(t.read_ambigous().unwrap(), AmbigousEvent::from_code(b"l"));
     assert!(matches!(t.read_ambigous(), Err(Error::StdInEof)));
 }
+
+#[test]
+fn test_poll_read() {
+    let mut t = Terminal::new(BufProvider::new(&[b"a\x1b[A", b"\x1b[>>Hb"]));
+    let mut cx = Context::from_waker(Waker::noop());
+    assert!(matches!(
+        t.poll_read(&mut cx),
+        Poll::Ready(Some(Ok(Event::KeyPress(Key {
+            code: KeyCode::Char('a'),
+            ..
+        }))))
+    ));
+    assert!(matches!(
+        t.poll_read(&mut cx),
+        Poll::Ready(Some(Ok(Event::KeyPress(Key {
+            code: KeyCode::Up,
+            ..
+        }))))
+    ));
+    // Unknown events are skipped.
+    assert!(matches!(
+        t.poll_read(&mut cx),
+        Poll::Ready(Some(Ok(Event::KeyPress(Key {
+            code: KeyCode::Char('b'),
+            ..
+        }))))
+    ));
+    assert!(matches!(t.poll_read(&mut cx), Poll::Ready(None)));
+
+    // Partial escape sequence doesn't block.
+    let mut t = Terminal::new(
+        MockProvider::new()
+            .bytes(b"a\x1b[1;")
+            .gap(Duration::from_millis(100))
+            .bytes(b"5A"),
+    );
+    assert!(matches!(
+        t.poll_read(&mut cx),
+        Poll::Ready(Some(Ok(Event::KeyPress(Key {
+            code: KeyCode::Char('a'),
+            ..
+        }))))
+    ));
+    assert!(t.poll_read(&mut cx).is_pending());
+    let Poll::Ready(Some(Ok(evt))) = t.poll_read(&mut cx) else {
+        panic!("Expected event.");
+    };
+    assert_eq!(
+        evt,
+        Event::KeyPress(Key::mcode(KeyCode::Up, Modifiers::CONTROL))
+    );
+}
+
+#[test]
+fn test_spawn_reader() {
+    let t = Terminal::new(BufProvider::new(&[b"a\x1b[5;3R", b"\x1b[>>Hb"]));
+    let (events, reader) = t.spawn_reader().unwrap();
+    // The channel is closed when the reader reaches eof.
+    assert_eq!(
+        events.iter().collect::<Vec<_>>(),
+        vec![
+            Event::KeyPress(Key::verbatim('a')),
+            Event::Status(Status::CursorPosition { x: 3, y: 5 }),
+            Event::KeyPress(Key::verbatim('b')),
+        ]
+    );
+    assert!(reader.stop().is_ok());
+
+    // The reader can be stopped while it waits for the rest of event.
+    let t = Terminal::new(
+        MockProvider::new()
+            .bytes(b"\x1b[1;")
+            .gap(Duration::MAX)
+            .bytes(b"5A"),
+    );
+    let (events, reader) = t.spawn_reader().unwrap();
+    std::thread::sleep(Duration::from_millis(10));
+    let mut t = reader.stop().unwrap();
+    assert!(events.try_recv().is_err());
+    // The unfinished event is not lost.
+    assert_eq!(
+        t.read().unwrap(),
+        Event::KeyPress(Key::mcode(KeyCode::Up, Modifiers::CONTROL))
+    );
+}
+
+#[test]
+fn test_read_mouse() {
+    let mut t = Terminal::new(BufProvider::new(&[
+        b"\x1b[M\xA0\x28\x2Fa",
+        b"\x1b[M\xC2\xA3\x28\x2Fb",
+    ]));
+    let mouse = |button| {
+        Event::Mouse(Mouse {
+            button,
+            event: mouse::Event::Down,
+            modifiers: Modifiers::NONE,
+            x: 8,
+            y: 15,
+            pixels: false,
+        })
+    };
+    assert_eq!(t.read().unwrap(), mouse(mouse::Button::Back));
+    assert_eq!(t.read().unwrap(), Event::KeyPress(Key::verbatim('a')));
+    assert_eq!(t.read().unwrap(), mouse(mouse::Button::Button11));
+    assert_eq!(t.read().unwrap(), Event::KeyPress(Key::verbatim('b')));
+}
+
+#[test]
+fn test_mouse_encoding() {
+    let mut t = Terminal::new(BufProvider::new(&[b"\x1b[M\xC2\xA3\x28/"]));
+    assert_eq!(t.mouse_encoding(), None);
+    t.set_mouse_encoding(Some(mouse::Encoding::Classic));
+    assert_eq!(
+        t.read().unwrap(),
+        Event::Mouse(Mouse {
+            button: mouse::Button::Button10,
+            event: mouse::Event::Drag,
+            modifiers: Modifiers::NONE,
+            x: 131,
+            y: 8,
+            pixels: false,
+        })
+    );
+    assert_eq!(t.read().unwrap(), Event::KeyPress(Key::verbatim('/')));
+
+    let mut t = Terminal::new(BufProvider::new(&[b"\x1b[<0;10;20M"]));
+    t.enable_mouse_encoding(mouse::Encoding::SgrPixels).unwrap();
+    assert_eq!(t.mouse_encoding(), Some(mouse::Encoding::SgrPixels));
+    assert!(matches!(
+        t.read().unwrap(),
+        Event::Mouse(Mouse {
+            x: 10,
+            y: 20,
+            pixels: true,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_read_paste() {
+    let data: &[&[u8]] = &[b"\x1b[200~a\rb\x1b[201~c\x1b[200~abcde\x1b[201~"];
+
+    let mut t = Terminal::new(BufProvider::new(data));
+    assert_eq!(t.paste_mode(), PasteMode::Chars);
+    assert_eq!(
+        t.read().unwrap(),
+        Event::StateChange(StateChange::BracketedPasteStart)
+    );
+    assert_eq!(t.read().unwrap(), Event::KeyPress(Key::verbatim('a')));
+
+    let mut t = Terminal::new(BufProvider::new(data));
+    t.set_paste_mode(PasteMode::Collect { max_len: 4 });
+    assert_eq!(t.read().unwrap(), Event::Paste("a\nb".into()));
+    assert_eq!(t.read().unwrap(), Event::KeyPress(Key::verbatim('c')));
+    assert_eq!(
+        t.read().unwrap(),
+        Event::StateChange(StateChange::BracketedPasteStart)
+    );
+    assert_eq!(t.read().unwrap(), Event::Paste("abcd".into()));
+    assert_eq!(t.read().unwrap(), Event::Paste("e".into()));
+    assert_eq!(
+        t.read().unwrap(),
+        Event::StateChange(StateChange::BracketedPasteEnd)
+    );
+    assert!(!t.is_bracketed_paste_open());
+    assert!(matches!(t.read(), Err(Error::StdInEof)));
+
+    // End of paste split between reads.
+    let data: &[&[u8]] = &[b"\x1b[200~ab\x1b[2", b"01~c"];
+
+    let mut t = Terminal::new(BufProvider::new(data));
+    t.set_paste_mode(PasteMode::Collect { max_len: 16 });
+    assert_eq!(t.read().unwrap(), Event::Paste("ab".into()));
+    assert_eq!(t.read().unwrap(), Event::KeyPress(Key::verbatim('c')));
+
+    let mut t = Terminal::new(BufProvider::new(data));
+    t.read().unwrap();
+    assert_eq!(t.read().unwrap(), Event::KeyPress(Key::verbatim('a')));
+    assert_eq!(t.read().unwrap(), Event::KeyPress(Key::verbatim('b')));
+    assert_eq!(
+        t.read().unwrap(),
+        Event::StateChange(StateChange::BracketedPasteEnd)
+    );
+    assert_eq!(t.read().unwrap(), Event::KeyPress(Key::verbatim('c')));
+}
+
+#[test]
+fn test_clipboard() {
+    assert_eq!(
+        Terminal::stdio().clipboard_limit(),
+        Some(DEFAULT_CLIPBOARD_LIMIT)
+    );
+
+    let mut t = Terminal::new(BufProvider::new(&[
+        b"a\x1b]52;c;aGVsbG8gdGhlcmU=\x1b\\",
+        b"b\x1b[?62;1c",
+    ]));
+    assert_eq!(
+        t.read_clipboard(Selection::Clipboard, Duration::from_secs(1))
+            .unwrap(),
+        Some("hello there".into())
+    );
+    assert_eq!(t.read().unwrap(), Event::KeyPress(Key::verbatim('a')));
+    assert_eq!(t.read().unwrap(), Event::KeyPress(Key::verbatim('b')));
+
+    let mut t = Terminal::new(BufProvider::new(&[b"x\x1b[?62;1c"]));
+    assert!(matches!(
+        t.read_clipboard(Selection::Clipboard, Duration::from_secs(1)),
+        Err(Error::ClipboardRefused)
+    ));
+    assert_eq!(t.read().unwrap(), Event::KeyPress(Key::verbatim('x')));
+
+    t.copy_to_clipboard(Selection::Primary, "hello").unwrap();
+    t.set_clipboard_limit(Some(4));
+    assert!(matches!(
+        t.copy_to_clipboard(Selection::Primary, "hello"),
+        Err(Error::ClipboardTooLarge { len: 8, max: 4 })
+    ));
+}
+
+#[test]
+fn test_history() {
+    let mut t = Terminal::new(BufProvider::new(&[
+        b"ls\r",
+        b"cd a\r",
+        b"ls -l\r",
+        b"\x1b[A\r",
+        b"l\x1b[A\x1b[A\r",
+        b"l\x1b[A\x1b[A\x1b[B\x1b[B\r",
+        b"\x1b[A\x1b[A\x1b[A\x1b[A\r",
+    ]));
+    t.set_history(MemHistory::new(3));
+    for s in ["ls", "cd a", "ls -l", "ls -l", "ls", "l", "ls -l"] {
+        assert_eq!(t.read_line().unwrap(), s);
+    }
+
+    let hist = t.history().unwrap();
+    let entries: Vec<_> = (0..hist.len()).map(|i| hist.get(i)).collect();
+    assert_eq!(entries, [Some("ls"), Some("l"), Some("ls -l")]);
+
+    // Input ended by eof is not added to history.
+    let mut t = Terminal::new(BufProvider::new(&[b"ls\r", b"partial"]));
+    t.set_history(MemHistory::new(3));
+    assert_eq!(t.read_line().unwrap(), "ls");
+    assert_eq!(t.read_line().unwrap(), "partial");
+    let hist = t.history().unwrap();
+    assert_eq!(hist.len(), 1);
+    assert_eq!(hist.get(0), Some("ls"));
+}
+
+#[test]
+fn test_file_history() {
+    let path = std::env::temp_dir()
+        .join(format!("termal-test-history-{}", std::process::id()));
+    std::fs::write(&path, "a\nb\\nc\na\nd\n").unwrap();
+
+    let mut hist = FileHistory::open(&path, 10).unwrap();
+    assert_eq!(hist.mem().iter().collect::<Vec<_>>(), ["b\nc", "a", "d"]);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "b\\nc\na\nd\n");
+
+    hist.add("b\nc").unwrap();
+    hist.add("d").unwrap();
+    assert_eq!(hist.mem().iter().collect::<Vec<_>>(), ["a", "b\nc", "d"]);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\nb\\nc\nd\n");
+
+    let hist = FileHistory::open(&path, 2).unwrap();
+    assert_eq!(hist.mem().iter().collect::<Vec<_>>(), ["b\nc", "d"]);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "b\\nc\nd\n");
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_history_search() {
+    let mut t = Terminal::new(BufProvider::new(&[
+        b"\x12ls\r",
+        b"\x12ls\x12\r",
+        b"\x12ls\x12\x12\x13\r",
+        b"x\x12cd\x1b",
+        b"\r",
+        b"\x12zz\r",
+        b"\x12cd\x7f\x7fls -\x1b[D\r",
+    ]));
+    let mut hist = MemHistory::new(10);
+    for e in ["ls -la", "cd src", "ls"] {
+        hist.add(e).unwrap();
+    }
+    t.set_history(hist);
+
+    for s in ["ls", "ls -la", "ls", "x", "", "ls -la"] {
+        assert_eq!(t.read_line().unwrap(), s);
+    }
+}
+
+#[test]
+fn test_completion() {
+    let mut t = Terminal::new(BufProvider::new(&[
+        b"git c\t\r",
+        b"git ch\t\r",
+        b"git ch\t\t\t\r",
+        b"git ch\t\t\t\t\t\r",
+        b"git ch\t\t\x1b[Z\x1b[Z\r",
+        b"x \t\r",
+    ]));
+
+    let words = ["checkout", "cherry-pick", "commit"];
+    for s in [
+        "git c",
+        "git che",
+        "git checkout",
+        "git che",
+        "git checkout",
+        "x c",
+    ] {
+        let mut r = TermRead::lines(&mut t);
+        r.set_completer(WordCompleter::new(words));
+        assert_eq!(r.read_str().unwrap(), s);
+    }
+
+    // Candidates that don't fit are counted with the configured message.
+    let mut t = Terminal::new(TestTerminal::new(16, 4).input(b"c\t\t"));
+    let mut r = TermRead::lines(&mut t);
+    r.set_completer(WordCompleter::new(words));
+    r.set_more_candidates_msg("+{}");
+    r.reshow().unwrap();
+    for _ in 0..3 {
+        assert!(!unsafe { r.read_one() }.unwrap());
+    }
+    t.io().screen().assert_text("checkout\ncheckout\n+2");
+}
+
+#[test]
+fn test_hints() {
+    let mut t = Terminal::new(BufProvider::new(&[
+        b"au\x1b[C\r",
+        b"au\x1b[D\x1b[C\x1b[C\r",
+        b"x\x1b[F\r",
+        b"a\x1b[F!\r",
+    ]));
+
+    for s in ["autocomplete", "autocomplete", "x", "autocomplete!"] {
+        let mut r = TermRead::lines(&mut t);
+        r.set_hinter(|buf: &[char], _| {
+            let s: String = buf.iter().collect();
+            "autocomplete".strip_prefix(&s).map(|h| h.to_string())
+        });
+        assert_eq!(r.read_str().unwrap(), s);
+    }
+
+    // Wide hint that wraps.
+    let mut t =
+        Terminal::new(TestTerminal::new(6, 3).input(b"a").input(b"b\r"));
+    let mut r = TermRead::lines(&mut t);
+    r.set_prompt("> ");
+    r.set_hinter(|buf: &[char], _| {
+        let s: String = buf.iter().collect();
+        "a日本語".strip_prefix(&s).map(|h| h.to_string())
+    });
+    assert_eq!(r.read_str().unwrap(), "ab");
+    t.io().screen().assert_text("\n> ab");
+}
+
+#[test]
+fn test_highlight() {
+    let mut t =
+        Terminal::new(TestTerminal::new(20, 3).input(b"ls -l\x1b[D\x1b[D-\r"));
+    let words = Rc::new(RefCell::new(vec![]));
+    let mut r = TermRead::lines(&mut t);
+    let w = words.clone();
+    r.set_highlighter(move |buf: &[char]| {
+        let s: String = buf.iter().collect();
+        w.borrow_mut().push(s.clone());
+        s.replace("ls", &formatc!("{'g}ls{'_}"))
+    });
+    assert_eq!(r.read_str().unwrap(), "ls --l");
+    // The buffer is highlighted once for each change.
+    assert_eq!(
+        *words.borrow(),
+        ["l", "ls", "ls ", "ls -", "ls -l", "ls --l"]
+    );
+    assert_eq!(t.io().screen().styled_text(), formatc!("{'g}ls{'_} --l"));
+
+    // Change after wrapped wide char restyles the start of the input.
+    let mut t = Terminal::new(TestTerminal::new(6, 3).input("日 lz\x1b[Ds\r"));
+    let mut r = TermRead::lines(&mut t);
+    r.set_prompt("> ");
+    r.set_highlighter(|buf: &[char]| {
+        let s: String = buf.iter().collect();
+        s.replace("ls", &formatc!("{'g}ls{'_}"))
+    });
+    assert_eq!(r.read_str().unwrap(), "日 lsz");
+    t.io().screen().assert_text("\n> 日 l\nsz");
+    assert_eq!(
+        t.io().screen().styled_text(),
+        formatc!("> 日 {'g}l{'_}\n{'g}s{'_}z")
+    );
+}
+
+#[test]
+fn test_multiline() {
+    let mut t = Terminal::new(BufProvider::new(&[
+        b"a\rb\x1b[Ac\x1b[B;\r",
+        b"x;\x1b\r\r",
+    ]));
+
+    for s in ["ac\nb;", "x;\n"] {
+        let mut r = TermRead::lines(&mut t);
+        r.set_continuation_prompt("> ");
+        r.set_multiline(|buf: &[char]| {
+            if buf.iter().rev().find(|c| !c.is_whitespace()) == Some(&';') {
+                Validation::Complete
+            } else {
+                Validation::Incomplete
+            }
+        });
+        assert_eq!(r.read_str().unwrap(), s);
+    }
+}
+
+#[test]
+fn test_keymap() {
+    let mut t = Terminal::new(BufProvider::new(&[
+        b"hello world\x17\x19\x19\r",
+        b"abc\x01\x0b\x19\x19\r",
+        b"ab\x14\r",
+        b"ab\x17\x1f\r",
+        b"ab cd\x1bbX\r",
+        b"a\x17b\x17\x19\x1by\r",
+        b"ab\x0cc\r",
+        b"ab\x18c\r",
+        b"\x14\r",
+        b"ab\x1a\r",
+    ]));
+
+    for s in [
+        "hello worldworld",
+        "abcabc",
+        "ba",
+        "ab",
+        "ab Xcd",
+        "a",
+        "abc",
+        "c",
+        "",
+        "ab",
+    ] {
+        let mut r = TermRead::lines(&mut t);
+        r.keymap_mut().bind(
+            Key::mcode(KeyCode::Char('x'), Modifiers::CONTROL),
+            Command::KillLineStart,
+        );
+        assert_eq!(r.read_str().unwrap(), s);
+    }
+
+    let mut t = Terminal::new(BufProvider::new(&[
+        b"abc",
+        b"\x1b",
+        b"0xA!\r",
+        b"foo bar baz",
+        b"\x1b",
+        b"bbdw\r",
+        b"foo bar",
+        b"\x1b",
+        b"0cwX\r",
+        b"foo bar",
+        b"\x1b",
+        b"0dwp\r",
+        b"foo\nbar",
+        b"\x1b",
+        b"ccX\r",
+        b"foo bar",
+        b"\x1b",
+        b"bd$\r",
+        b"foo",
+        b"\x1b",
+        b"dd\r",
+        b"foo",
+        b"\x1b",
+        b"dxi!\r",
+    ]));
+    for s in [
+        "bc!", "foo baz", "X bar", "bfoo ar", "X", "foo ", "", "fo!o",
+    ] {
+        let mut r = TermRead::lines(&mut t);
+        r.set_keymap(Keymap::vi());
+        assert_eq!(r.read_str().unwrap(), s);
+    }
+}
+
+#[test]
+fn test_undo() {
+    let mut t = Terminal::new(BufProvider::new(&[
+        b"ab cd\x1f\r",
+        b"ab\x1b[Dc\x1f\r",
+        b"ab\x1f\x1b/\r",
+        b"ab\x17c\x1f\x1f\x1f\x1b/\r",
+        b"ch\t\x1f\r",
+        b"\x1f\r",
+    ]));
+
+    for s in ["", "ab", "ab", "ab", "ch"] {
+        let mut r = TermRead::lines(&mut t);
+        r.set_completer(WordCompleter::new(["cheese"]));
+        assert_eq!(r.read_str().unwrap(), s);
+    }
+
+    let mut r = TermRead::lines(&mut t);
+    assert_eq!(r.edit("abc", None).unwrap(), "abc");
+}
+
+#[test]
+fn test_masked() {
+    let cases: [(&[u8], _, _, _); 3] = [
+        (b"secret\r", Echo::Hidden, "secret", "> "),
+        (
+            b"\x1b[Ae\xcc\x81x\x7f\r",
+            Echo::Mask('*'),
+            "e\u{301}",
+            "> *",
+        ),
+        // Killed text is not saved and changes can't be undone.
+        (b"ab\x17\x19c\x1f\r", Echo::Mask('*'), "c", "> *"),
+    ];
+
+    for (input, echo, res, screen) in cases {
+        let mut t = Terminal::new(TestTerminal::new(10, 4).input(input));
+        t.set_history(MemHistory::new(10));
+        t.history_mut().unwrap().add("old").unwrap();
+
+        assert_eq!(t.prompt_masked("> ", echo).unwrap(), res);
+        assert_eq!(t.history().unwrap().len(), 1);
+        t.io().screen().assert_text(screen);
+    }
+}
+
+#[test]
+fn test_validation() {
+    let mut t =
+        Terminal::new(BufProvider::new(&[b"1a2b34\r", b"\r5\r", b"1\t\r"]));
+
+    let conf = ReadConf {
+        filter: Some(Arc::new(|buf: &[char], _, c: char| {
+            c.is_ascii_digit() && buf.len() < 3
+        })),
+        validator: Some(Arc::new(|buf: &[char]| {
+            if buf.is_empty() {
+                Validation::Invalid("Enter a number.".into())
+            } else {
+                Validation::Complete
+            }
+        })),
+        ..Default::default()
+    };
+
+    for s in ["123", "5"] {
+        let mut r =
+            TermRead::from_config(&mut t, KeyCode::Enter, conf.clone());
+        assert_eq!(r.read_str().unwrap(), s);
+    }
+
+    // The filter applies to all inserted text.
+    let mut r = TermRead::from_config(&mut t, KeyCode::Enter, conf.clone());
+    r.set_completer(WordCompleter::new(["12345"]));
+    assert_eq!(r.read_str().unwrap(), "123");
+    r.set_edit("1a2b34", None);
+    assert_eq!(r.get_input(), ['1', '2', '3']);
+    r.splice(0..1, "x9".chars());
+    assert_eq!(r.get_input(), ['9', '2', '3']);
+    r.set_buf(vec!['a', '4', '5', '6', '7'], None);
+    assert_eq!(r.get_input(), ['4', '5', '6']);
+
+    // Yanked text is filtered.
+    let mut t = Terminal::new(BufProvider::new(&[b"12\x1734\x19\x1by\r"]));
+    let mut r = TermRead::from_config(&mut t, KeyCode::Enter, conf.clone());
+    assert_eq!(r.read_str().unwrap(), "341");
+
+    // Incomplete input is not submitted in single line mode.
+    let mut t = Terminal::new(TestTerminal::new(30, 3).input(b"1\r"));
+    let conf = ReadConf {
+        validator: Some(Arc::new(|buf: &[char]| {
+            if buf.len() < 2 {
+                Validation::Incomplete
+            } else {
+                Validation::Complete
+            }
+        })),
+        ..Default::default()
+    };
+    let mut r = TermRead::from_config(&mut t, KeyCode::Enter, conf.clone());
+    r.set_prompt("> ");
+    r.reshow().unwrap();
+    for _ in 0..2 {
+        assert!(!unsafe { r.read_one() }.unwrap());
+    }
+    t.io()
+        .screen()
+        .assert_text("\n> 1\nThe input is incomplete.");
+
+    let mut t = Terminal::new(TestTerminal::new(30, 3).input(b"1\r"));
+    let conf = ReadConf {
+        incomplete_msg: "Enter two digits.".into(),
+        ..conf
+    };
+    let mut r = TermRead::from_config(&mut t, KeyCode::Enter, conf);
+    r.reshow().unwrap();
+    for _ in 0..2 {
+        assert!(!unsafe { r.read_one() }.unwrap());
+    }
+    t.io().screen().assert_text("1\nEnter two digits.");
+}
+
+#[test]
+fn test_graphemes() {
+    let cases: [(&str, _, _, _, _); 4] = [
+        // Combining char.
+        (
+            "e\u{301}x\x1b[D\x1b[Da\r",
+            6,
+            "ae\u{301}x",
+            "ae\u{301}x",
+            (3, 0),
+        ),
+        // ZWJ sequence is deleted as one grapheme.
+        ("a👨\u{200d}👩\u{200d}👧\x7f\r", 6, "a", "a", (1, 0)),
+        // Wide chars are transposed as whole.
+        ("漢字b\x1b[D\x1b[D\x14\r", 6, "字漢b", "字漢b", (5, 0)),
+        // Wide char that doesn't fit is moved to the next line.
+        ("漢字漢\r", 5, "漢字漢", "漢字\n漢", (2, 1)),
+    ];
+
+    for (input, w, res, screen, cur) in cases {
+        let mut t = Terminal::new(TestTerminal::new(w, 3).input(input));
+        let mut r = TermRead::lines(&mut t);
+        assert_eq!(r.read_str().unwrap(), res);
+        t.io().screen().assert_text(screen);
+        assert_eq!(t.io().screen().cursor(), cur);
+    }
+}
+
+#[test]
+fn test_screen() {
+    let mut s = Screen::new(6, 3);
+    s.feed(b"abcdefgh\r\nxy\x1b[1;3Hz\x1b[2;2H\x1b[K");
+    s.assert_text(
+        "
+abzdef
+g
+xy",
+    );
+
+    s.feed(b"\x1b[3;1H\x1b[31m\xe6\xbc\xa2\x1b[0m!\n\rnext\x1b[");
+    s.assert_text(
+        "
+g
+\u{6f22}!
+next",
+    );
+    assert_eq!(s.styled_line(1), formatc!("{'dr}\u{6f22}{'_}!"));
+    assert_eq!(s.cursor(), (4, 2));
+
+    s.feed(b"2J\x1b[?1049h\x1b[Halt");
+    assert!(s.is_alternate());
+    s.assert_text("alt");
+    s.feed(b"\x1b[?1049l");
+    s.assert_text("");
+    assert_eq!(s.cursor(), (4, 2));
+
+    s.feed(b"\x1b[H1\r\n2\r\n3\x1b[2;3r\x1b[3;1H\n4\x1b[r");
+    s.assert_text(
+        "
+1
+3
+4",
+    );
+
+    // ZWJ sequence is one grapheme cluster.
+    s.feed("\x1b[2J\x1b[Ha👨\u{200d}👩\u{200d}👧b".as_bytes());
+    s.assert_text("a👨\u{200d}👩\u{200d}👧b");
+    assert_eq!(s.cursor(), (4, 0));
+
+    // Huge arguments don't overflow.
+    let max = usize::MAX;
+    s.feed(format!("\x1b[{max}C\x1b[{max}B\x1b[{max}X\x1b[{max}E").as_bytes());
+    assert_eq!(s.cursor(), (0, 2));
+}
+
+#[test]
+fn test_test_terminal() {
+    let mut t = Terminal::new(
+        TestTerminal::new(10, 4)
+            .input(b"abcdefghijkl")
+            .input(b"\x1b[D\x7f\x7f\r"),
+    );
+
+    let mut r = TermRead::lines(&mut t);
+    r.set_prompt("> ");
+    assert_eq!(r.read_str().unwrap(), "abcdefghil");
+    t.io().screen().assert_text(
+        "
+> abcdefgh
+il",
+    );
+    assert_eq!(t.io().screen().cursor(), (2, 1));
+    assert!(!t.io().has_input());
+}
+
+#[test]
+fn test_mock_provider() {
+    let keys = [
+        Key::mcode(KeyCode::Char('a'), Modifiers::CONTROL),
+        Key::mcode(KeyCode::Char('x'), Modifiers::ALT),
+        Key::mcode(KeyCode::Up, Modifiers::SHIFT),
+        Key::code(KeyCode::F2),
+        Key::mcode(KeyCode::F12, Modifiers::CONTROL),
+        Key::code(KeyCode::PgDown),
+        Key::mcode(KeyCode::Tab, Modifiers::SHIFT),
+        Key::code(KeyCode::Esc),
+    ];
+    let mouse = Mouse {
+        button: mouse::Button::Right,
+        event: mouse::Event::Up,
+        modifiers: Modifiers::CONTROL | Modifiers::SHIFT,
+        x: 8,
+        y: 15,
+        pixels: false,
+    };
+    let status = Status::CursorPosition { x: 17, y: 10 };
+
+    let mut t = Terminal::new(
+        MockProvider::new()
+            .keys(keys.iter().cloned())
+            .mouse(mouse)
+            .status(&status)
+            .paste("a\rb")
+            .gap(Duration::from_millis(100))
+            .text("c")
+            .size(80, 24),
+    );
+    t.set_paste_mode(PasteMode::Collect { max_len: 16 });
+
+    for k in keys {
+        assert_eq!(t.read().unwrap(), Event::KeyPress(k));
+    }
+    assert_eq!(t.read().unwrap(), Event::Mouse(mouse));
+    assert_eq!(t.read().unwrap(), Event::Status(status));
+    assert_eq!(t.read().unwrap(), Event::Paste("a\nb".into()));
+
+    let timeout = Duration::from_millis(60);
+    assert_eq!(t.read_timeout(timeout).unwrap(), None);
+    assert_eq!(
+        t.read_timeout(timeout).unwrap(),
+        Some(Event::KeyPress(Key::verbatim('c')))
+    );
+    assert!(!t.io().has_input());
+    assert!(matches!(t.read(), Err(Error::StdInEof)));
+
+    assert_eq!(t.term_size().unwrap().char_width, 80);
+    t.write_all(b"hello").unwrap();
+    assert_eq!(t.io().output_str(), "hello");
+    assert_eq!(t.io_mut().take_output(), b"hello");
+    assert!(t.io().output().is_empty());
+
+    let mouse = Mouse {
+        x: 130,
+        y: 245,
+        pixels: true,
+        ..mouse
+    };
+    let mut t = Terminal::new(MockProvider::new().mouse(mouse));
+    t.set_mouse_encoding(Some(mouse::Encoding::SgrPixels));
+    assert_eq!(t.read().unwrap(), Event::Mouse(mouse));
+    assert!(matches!(t.term_size(), Err(Error::Io(_))));
+}